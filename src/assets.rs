@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use bevy::asset::{LoadState, UntypedAssetId};
+use bevy::utils::HashMap;
+use crate::GameState;
+use crate::audio::effects::SoundPack;
+use crate::audio::music::Track;
+use crate::game::{check_point, player, power_up};
+use crate::shop::{CloudSkin, PlayerSkin};
+
+// every file the game needs, loaded up front behind the loading screen
+// spawn sites clone handles from here instead of loading by path
+#[derive(Resource)]
+pub struct GameAssets {
+    pub font: Handle<Font>,
+    pub background: Handle<Image>,
+    pub logo: Handle<Image>,
+    // the skins being worn, see shop::wear_skins
+    pub player: Handle<Image>,
+    pub player_layout: Handle<TextureAtlasLayout>,
+    pub cloud: Handle<Image>,
+    pub player_skins: HashMap<PlayerSkin, Handle<Image>>,
+    pub cloud_skins: HashMap<CloudSkin, Handle<Image>>,
+    pub crumbling_cloud: Handle<Image>,
+    pub drifting_cloud: Handle<Image>,
+    pub spiked_cloud: Handle<Image>,
+    pub ground_cloud: Handle<Image>,
+    pub hour_glass: Handle<Image>,
+    pub hour_glass_layout: Handle<TextureAtlasLayout>,
+    pub power_ups: Handle<Image>,
+    pub power_ups_layout: Handle<TextureAtlasLayout>,
+    pub coin: Handle<Image>,
+    // keyed by path, everything the sound pack can play
+    pub sounds: HashMap<String, Handle<AudioSource>>,
+    pub music: HashMap<Track, Handle<AudioSource>>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let sound_paths: Vec<String> = world
+            .get_resource_or_insert_with(SoundPack::default)
+            .0
+            .values()
+            .flat_map(|sound| sound.paths.iter().cloned())
+            .collect();
+        let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+        let player_layout = layouts.add(player::atlas_layout());
+        let hour_glass_layout = layouts.add(check_point::atlas_layout());
+        let power_ups_layout = layouts.add(power_up::atlas_layout());
+
+        let asset_server = world.resource::<AssetServer>();
+        let player_skins: HashMap<PlayerSkin, Handle<Image>> = PlayerSkin::ALL
+            .iter()
+            .map(|skin| (*skin, asset_server.load(skin.path())))
+            .collect();
+        let cloud_skins: HashMap<CloudSkin, Handle<Image>> = CloudSkin::ALL
+            .iter()
+            .map(|skin| (*skin, asset_server.load(skin.path())))
+            .collect();
+        GameAssets {
+            font: asset_server.load("FiraSans-Regular.ttf"),
+            background: asset_server.load("background_small.png"),
+            logo: asset_server.load("logo.png"),
+            player: player_skins[&PlayerSkin::default()].clone(),
+            player_layout,
+            cloud: cloud_skins[&CloudSkin::default()].clone(),
+            player_skins,
+            cloud_skins,
+            crumbling_cloud: asset_server.load("crumbling_cloud.png"),
+            drifting_cloud: asset_server.load("drifting_cloud.png"),
+            spiked_cloud: asset_server.load("spiked_cloud.png"),
+            ground_cloud: asset_server.load("ground_cloud.png"),
+            hour_glass: asset_server.load("hour_glass.png"),
+            hour_glass_layout,
+            power_ups: asset_server.load("power_ups.png"),
+            power_ups_layout,
+            coin: asset_server.load("coin.png"),
+            sounds: sound_paths
+                .into_iter()
+                .map(|path| (path.clone(), asset_server.load(path)))
+                .collect(),
+            music: Track::ALL
+                .iter()
+                .map(|track| (*track, asset_server.load(track.path())))
+                .collect(),
+        }
+    }
+}
+
+impl GameAssets {
+    // the game can't run without these
+    fn required(&self) -> Vec<UntypedAssetId> {
+        let mut ids = vec![
+            self.font.id().untyped(),
+            self.background.id().untyped(),
+            self.logo.id().untyped(),
+            self.crumbling_cloud.id().untyped(),
+            self.drifting_cloud.id().untyped(),
+            self.spiked_cloud.id().untyped(),
+            self.ground_cloud.id().untyped(),
+            self.hour_glass.id().untyped(),
+            self.power_ups.id().untyped(),
+            self.coin.id().untyped(),
+        ];
+        ids.extend(self.player_skins.values().map(|skin| skin.id().untyped()));
+        ids.extend(self.cloud_skins.values().map(|skin| skin.id().untyped()));
+        ids.extend(self.sounds.values().map(|sound| sound.id().untyped()));
+        ids
+    }
+
+    // music is nice to have, missing tracks only get a warning
+    fn optional(&self) -> Vec<UntypedAssetId> {
+        self.music.values().map(|track| track.id().untyped()).collect()
+    }
+
+}
+
+#[derive(Component)]
+struct LoadingText;
+
+#[derive(Component)]
+struct LoadingBar;
+
+const BAR_COLOR: Color = Color::srgb(0.69, 0.67, 1.0);
+
+pub struct AssetsPlugin;
+
+impl Plugin for AssetsPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .init_resource::<GameAssets>()
+            .add_systems(OnEnter(GameState::Loading), load_loading_screen)
+            .add_systems(Update, check_loading.run_if(in_state(GameState::Loading)))
+            .add_systems(PreUpdate, load_sound_pack.run_if(resource_changed::<SoundPack>));
+    }
+}
+
+// a pack swapped in after startup gets its new sounds loaded here
+fn load_sound_pack(pack: Res<SoundPack>,
+    mut assets: ResMut<GameAssets>,
+    asset_server: Res<AssetServer>,
+)
+{
+    for path in pack.0.values().flat_map(|sound| sound.paths.iter()) {
+        if !assets.sounds.contains_key(path) {
+            assets.sounds.insert(path.clone(), asset_server.load(path.clone()));
+        }
+    }
+}
+
+// the font is still loading too, so this uses bevy's built in one
+fn load_loading_screen(mut commands: Commands) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        ..default()
+    }, StateScoped(GameState::Loading)))
+    .with_children(|parent|{
+        parent.spawn((TextBundle::from_section(
+            "Loading"
+            , TextStyle {
+                font_size: 25.,
+                color: Color::WHITE,
+                ..default()
+            }
+        ).with_text_justify(JustifyText::Center), LoadingText));
+        parent.spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(300.),
+                height: Val::Px(20.),
+                border: UiRect::all(Val::Px(2.)),
+                ..default()
+            },
+            border_color: BorderColor(Color::WHITE),
+            ..default()
+        })
+        .with_children(|parent|{
+            parent.spawn((NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.),
+                    height: Val::Percent(100.),
+                    ..default()
+                },
+                background_color: BackgroundColor(BAR_COLOR),
+                ..default()
+            }, LoadingBar));
+        });
+    });
+}
+
+// heads to the menu once everything has either loaded or, for optional files, failed
+// a required file that fails keeps the loading screen up with its path on it
+fn check_loading(assets: Res<GameAssets>,
+    asset_server: Res<AssetServer>,
+    mut text: Query<&mut Text, With<LoadingText>>,
+    mut bar: Query<&mut Style, With<LoadingBar>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut reported: Local<bool>,
+)
+{
+    let required = assets.required();
+    let optional = assets.optional();
+    let state = |id: &UntypedAssetId| asset_server.get_load_state(*id);
+    let path = |id: &UntypedAssetId| asset_server
+        .get_path(*id)
+        .map(|path| path.to_string())
+        .unwrap_or_else(|| format!("{id:?}"));
+
+    let missing: Vec<String> = required
+        .iter()
+        .filter(|id| matches!(state(id), Some(LoadState::Failed(_))))
+        .map(path)
+        .collect();
+    let done = required
+        .iter()
+        .chain(optional.iter())
+        .filter(|id| matches!(state(id), Some(LoadState::Loaded) | Some(LoadState::Failed(_))))
+        .count();
+    let total = required.len() + optional.len();
+
+    let message = if missing.is_empty() {
+        format!("Loading {}%", done * 100 / total)
+    }
+    else {
+        if !*reported {
+            error!("could not load required assets: {}", missing.join(", "));
+            *reported = true;
+        }
+        format!("Could not load:\n{}", missing.join("\n"))
+    };
+    for mut text in &mut text {
+        if text.sections[0].value != message {
+            text.sections[0].value = message.clone();
+        }
+    }
+    for mut style in &mut bar {
+        style.width = Val::Percent(100. * done as f32 / total as f32);
+    }
+
+    if missing.is_empty() && done == total {
+        for id in optional.iter().filter(|id| matches!(state(id), Some(LoadState::Failed(_)))) {
+            warn!("could not load {}, carrying on without it", path(id));
+        }
+        game_state.set(GameState::Menu);
+    }
+}
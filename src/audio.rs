@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use crate::settings::{Settings, SettingsScreen};
+
+pub mod music;
+pub mod effects;
+use music::MusicTrack;
+
+const MUTE_KEY: KeyCode = KeyCode::KeyM;
+
+// which volume slider a sound answers to
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundKind {
+    Sfx,
+    Music,
+}
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .init_resource::<effects::SoundPack>()
+            .add_systems(Update, effects::play_effects)
+            .add_systems(Update, (
+                toggle_mute.run_if(in_state(SettingsScreen::Closed)),
+                apply_volume.run_if(resource_changed::<Settings>),
+            ).chain())
+            .add_plugins(music::MusicPlugin);
+    }
+}
+
+// sounds already playing follow the sliders too, music sets its own as it fades
+fn apply_volume(settings: Res<Settings>, sinks: Query<(&AudioSink, &SoundKind), Without<MusicTrack>>) {
+    for (sink, kind) in &sinks {
+        sink.set_volume(settings.audio.volume(*kind));
+    }
+}
+
+// unless the player has bound M to a direction
+fn toggle_mute(keyboard_input: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    let bindings = &settings.bindings;
+    if !keyboard_input.just_pressed(MUTE_KEY) ||
+    bindings.left.contains(&MUTE_KEY) || bindings.right.contains(&MUTE_KEY)
+    {
+        return;
+    }
+    settings.audio.muted = !settings.audio.muted;
+}
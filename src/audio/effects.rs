@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use bevy::audio::Volume;
+use bevy::utils::HashMap;
+use rand::Rng;
+use crate::assets::GameAssets;
+use crate::game::GameEvent;
+use crate::settings::Settings;
+use super::SoundKind;
+
+// how one game event sounds, a random path is picked from paths each time
+#[derive(Clone, Debug)]
+pub struct EffectSound {
+    pub paths: Vec<String>,
+    pub volume: f32,
+    pub pitch: f32,
+    // each play is nudged up or down by up to this much
+    pub volume_variation: f32,
+    pub pitch_variation: f32,
+    // the oldest copy is cut off to make room past this many
+    pub max_playing: usize,
+}
+
+impl Default for EffectSound {
+    fn default() -> Self {
+        EffectSound {
+            paths: Vec::new(),
+            volume: 1.,
+            pitch: 1.,
+            volume_variation: 0.,
+            pitch_variation: 0.,
+            max_playing: 1,
+        }
+    }
+}
+
+impl EffectSound {
+    pub fn new(path: &str) -> Self {
+        EffectSound {
+            paths: vec![path.to_string()],
+            ..default()
+        }
+    }
+}
+
+// which sound each game event makes, insert a different one before SoundPlugin to swap packs
+// events missing from the pack stay silent
+#[derive(Resource, Clone, Debug)]
+pub struct SoundPack(pub HashMap<GameEvent, EffectSound>);
+
+impl Default for SoundPack {
+    fn default() -> Self {
+        SoundPack(HashMap::from([
+            (GameEvent::Jumped, EffectSound {
+                volume_variation: 0.1,
+                pitch_variation: 0.1,
+                max_playing: 3,
+                ..EffectSound::new("footstep_wood_004.ogg")
+            }),
+            (GameEvent::WrongDirection, EffectSound::new("woops.ogg")),
+            (GameEvent::CheckpointReached, EffectSound::new("impactGlass_heavy_002.ogg")),
+            (GameEvent::TimerExpired, EffectSound {
+                pitch: 0.8,
+                ..EffectSound::new("woops.ogg")
+            }),
+            (GameEvent::Landed, EffectSound {
+                pitch: 0.6,
+                volume_variation: 0.1,
+                max_playing: 2,
+                ..EffectSound::new("impactGlass_heavy_002.ogg")
+            }),
+            (GameEvent::Spiked, EffectSound {
+                pitch: 1.4,
+                volume: 0.8,
+                ..EffectSound::new("woops.ogg")
+            }),
+            (GameEvent::PoweredUp, EffectSound {
+                pitch: 1.8,
+                volume: 0.6,
+                ..EffectSound::new("impactGlass_heavy_002.ogg")
+            }),
+            (GameEvent::CoinCollected, EffectSound {
+                pitch: 2.2,
+                volume: 0.5,
+                ..EffectSound::new("impactGlass_heavy_002.ogg")
+            }),
+            (GameEvent::ShieldUsed, EffectSound {
+                pitch: 1.2,
+                ..EffectSound::new("impactGlass_heavy_002.ogg")
+            }),
+            (GameEvent::Crumbled, EffectSound {
+                volume: 0.6,
+                pitch: 0.5,
+                pitch_variation: 0.05,
+                ..EffectSound::new("impactGlass_heavy_002.ogg")
+            }),
+        ]))
+    }
+}
+
+// a sound effect that's playing, started is in seconds since startup
+#[derive(Component, Debug)]
+pub struct Effect {
+    pub event: GameEvent,
+    pub started: f32,
+}
+
+pub fn play_effects(mut commands: Commands,
+    mut events: EventReader<GameEvent>,
+    playing: Query<(Entity, &Effect)>,
+    pack: Res<SoundPack>,
+    assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+)
+{
+    let mut rng = rand::thread_rng();
+    // sounds spawned this frame count towards the cap as well
+    let mut live: Vec<(Entity, GameEvent, f32)> = playing
+        .iter()
+        .map(|(entity, effect)| (entity, effect.event, effect.started))
+        .collect();
+    live.sort_by(|a, b| a.2.total_cmp(&b.2));
+    for event in events.read() {
+        let Some(sound) = pack.0.get(event) else {
+            continue;
+        };
+        if sound.paths.is_empty() || sound.max_playing == 0 {
+            continue;
+        }
+
+        // make room by cutting off the oldest copies
+        while live.iter().filter(|(_, playing, _)| playing == event).count() >= sound.max_playing {
+            let oldest = live.iter().position(|(_, playing, _)| playing == event).unwrap();
+            let (entity, _, _) = live.remove(oldest);
+            commands.entity(entity).despawn();
+        }
+
+        let path = &sound.paths[rng.gen_range(0..sound.paths.len())];
+        let Some(source) = assets.sounds.get(path) else {
+            continue;
+        };
+        let volume = sound.volume + rng.gen_range(-1.0..=1.0) * sound.volume_variation;
+        let pitch = sound.pitch + rng.gen_range(-1.0..=1.0) * sound.pitch_variation;
+        let started = time.elapsed_seconds();
+        let entity = commands.spawn((AudioBundle {
+            source: source.clone(),
+            settings: PlaybackSettings::DESPAWN
+                .with_volume(Volume::new(volume.max(0.) * settings.audio.volume(SoundKind::Sfx)))
+                .with_speed(pitch.max(0.1)),
+        }, Effect {
+            event: *event,
+            started,
+        }, SoundKind::Sfx)).id();
+        live.push((entity, *event, started));
+    }
+}
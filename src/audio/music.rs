@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy::audio::Volume;
+use crate::GameState;
+use crate::assets::GameAssets;
+use crate::game::Game;
+use crate::settings::Settings;
+use super::SoundKind;
+
+// seconds for one track to fade out while the next fades in
+const CROSSFADE_TIME: f32 = 1.5;
+// the playing track speeds up over the last seconds of a checkpoint
+const URGENT_TIME: f32 = 5.;
+const URGENT_SPEED: f32 = 1.25;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Track {
+    Menu,
+    Playing,
+    GameOver,
+}
+
+impl Track {
+    pub const ALL: [Track; 3] = [Track::Menu, Track::Playing, Track::GameOver];
+
+    pub fn path(&self) -> &'static str {
+        match self {
+            Track::Menu => "music/menu.ogg",
+            Track::Playing => "music/playing.ogg",
+            Track::GameOver => "music/game_over.ogg",
+        }
+    }
+
+    // game over is a stinger, the rest loop
+    fn playback(&self) -> PlaybackSettings {
+        match self {
+            Track::GameOver => PlaybackSettings::ONCE,
+            _ => PlaybackSettings::LOOP,
+        }
+    }
+}
+
+// fade runs from 0 (silent) to 1 (full music volume)
+#[derive(Component, Debug)]
+pub struct MusicTrack {
+    pub track: Track,
+    pub fade: f32,
+    pub fading_out: bool,
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .add_systems(OnEnter(GameState::Menu), play(Track::Menu))
+            .add_systems(OnEnter(GameState::Playing), play(Track::Playing))
+            .add_systems(OnEnter(GameState::GameOver), play(Track::GameOver))
+            .add_systems(Update, (
+                crossfade,
+                intensify.run_if(in_state(GameState::Playing)),
+            ).chain());
+    }
+}
+
+// fades out whatever is on and fades the track in, unless it's already the one playing
+fn play(track: Track) -> impl Fn(Commands, Query<&mut MusicTrack>, Res<GameAssets>) {
+    move |mut commands: Commands, mut tracks: Query<&mut MusicTrack>, assets: Res<GameAssets>| {
+        if tracks.iter().any(|playing| playing.track == track && !playing.fading_out) {
+            return;
+        }
+        for mut playing in &mut tracks {
+            playing.fading_out = true;
+        }
+        commands.spawn((AudioBundle {
+            source: assets.music[&track].clone(),
+            settings: track.playback().with_volume(Volume::new(0.)),
+        }, MusicTrack {
+            track,
+            fade: 0.,
+            fading_out: false,
+        }, SoundKind::Music));
+    }
+}
+
+fn crossfade(mut commands: Commands,
+    mut tracks: Query<(Entity, &mut MusicTrack, Option<&AudioSink>)>,
+    time: Res<Time>,
+    settings: Res<Settings>,
+)
+{
+    let step = time.delta_seconds() / CROSSFADE_TIME;
+    for (entity, mut music, sink) in &mut tracks {
+        if music.fading_out {
+            music.fade = (music.fade - step).max(0.);
+            if music.fade == 0. {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+        else {
+            music.fade = (music.fade + step).min(1.);
+        }
+        if let Some(sink) = sink {
+            sink.set_volume(settings.audio.volume(SoundKind::Music) * music.fade);
+        }
+    }
+}
+
+// how close the checkpoint timer is to running out, 0 until it's urgent then up to 1
+pub fn urgency(game: &Game) -> f32 {
+    let remaining = game.climb.clock().remaining();
+    (1. - remaining / URGENT_TIME).clamp(0., 1.)
+}
+
+fn intensify(game: Res<Game>, tracks: Query<(&MusicTrack, &AudioSink)>) {
+    let speed = 1. + (URGENT_SPEED - 1.) * urgency(&game);
+    for (music, sink) in &tracks {
+        if music.track == Track::Playing && sink.speed() != speed {
+            sink.set_speed(speed);
+        }
+    }
+}
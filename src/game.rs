@@ -1,45 +1,144 @@
 use bevy::prelude::*;
 
-mod player;
-mod platform;
-mod score;
-mod check_point;
+pub mod player;
+pub mod platform;
+pub mod score;
+pub mod check_point;
 mod animation;
-use player::Player;
-use player::PlayerAction;
+pub mod seed;
+pub mod daily;
+pub mod input;
+pub mod replay;
+pub mod pause;
+pub mod curve;
+pub mod power_up;
+pub mod coin;
+pub mod popup;
+use player::{Fall, JumpArc, Player};
+use rand::Rng;
+pub use player::PlayerAction;
 use score::Score;
 use check_point::CheckPoint;
+use platform::{Platform, PlatformCulling, Platforms};
 use super::GameState;
+use crate::save::SaveData;
+use crate::assets::GameAssets;
+use seed::TowerSeed;
+use daily::UtcDate;
+use input::DirectionInput;
+use replay::{Playback, Recorder};
+use pause::PlayState;
+use curve::Curve;
+pub use crate::rules::{Direction, Location};
+use crate::rules::{Climb, ClimbConfig, Difficulty, PlatformKind, Step, START_X, START_Y};
+use crate::settings::Settings;
 
 const GROUND_OFFSET: f32 = 200.;
-const START_X: f32 = 0.0;
-const START_Y: f32 = -200.;
-const X_INC: f32 = 70.;
-const Y_INC: f32 = 40.;
 const PLAYER_Z: f32 = 2.0;
 const PLATFORM_Z: f32 = 1.0;
 const CAMERA_Z: f32 = 10.0;
+// how far above the player the camera sits
+const CAMERA_OFFSET: f32 = 100.;
+// how long the player lies on the ground before the game over screen
+const LANDED_TIME: f32 = 0.6;
+const SHAKE_TIME: f32 = 0.4;
+// camera offset per unit of impact speed
+const SHAKE_PER_SPEED: f32 = 0.01;
 
-#[derive(Clone)]
-pub struct Location {
-    pub x: f32,
-    pub y: f32,
+// shakes the camera for a little while, strength is the furthest it moves
+#[derive(Resource, Default)]
+pub struct CameraShake {
+    time_left: f32,
+    strength: f32,
+    offset: Vec2,
+}
+
+impl CameraShake {
+    pub fn start(&mut self, strength: f32) {
+        self.time_left = SHAKE_TIME;
+        self.strength = self.strength.max(strength);
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    fn update(&mut self, secs: f32) -> Vec2 {
+        self.time_left = (self.time_left - secs).max(0.);
+        if self.time_left == 0. {
+            self.strength = 0.;
+            self.offset = Vec2::ZERO;
+            return self.offset;
+        }
+        let mut rng = rand::thread_rng();
+        let strength = self.strength * self.time_left / SHAKE_TIME;
+        self.offset = Vec2::new(
+            rng.gen_range(-1.0..=1.0) * strength,
+            rng.gen_range(-1.0..=1.0) * strength,
+        );
+        self.offset
+    }
+}
+
+// how the player and camera move, purely visual, the climb decides where everyone is
+#[derive(Resource)]
+pub struct Motion {
+    // seconds from one platform to the next
+    pub jump_time: f32,
+    // how far the arc rises above a straight line
+    pub jump_height: f32,
+    // higher catches up with the player faster
+    pub camera_easing: f32,
+    // extra distance the camera looks up the tower
+    pub look_ahead: f32,
+    // whether a fall bounces off the ground cloud before coming to rest
+    pub bounce: bool,
 }
 
-impl Default for Location {
+impl Default for Motion {
     fn default() -> Self {
-        Location {
-            x: START_X,
-            y: START_Y,
+        Motion {
+            jump_time: 0.15,
+            jump_height: 25.,
+            camera_easing: 8.,
+            look_ahead: 0.,
+            bounce: true,
         }
     }
 }
 
-#[derive(PartialEq, Default)]
-enum Direction {
+#[derive(Resource, Clone, Copy, PartialEq, Default)]
+pub enum GameMode {
     #[default]
-    Left,
-    Right,
+    Classic,
+    Daily(UtcDate),
+    Replay,
+}
+
+// what happened during a run, for anything that wants to react to it like the sound effects
+#[derive(Event, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum GameEvent {
+    Jumped,
+    WrongDirection,
+    CheckpointReached,
+    TimerExpired,
+    Landed,
+    // sent after WrongDirection or TimerExpired when a spare life takes the player back
+    LifeLost,
+    // a decoy branch or crumbling platform fell away under the player, sent before LifeLost or the fall
+    Crumbled,
+    // landed on spikes, the clock lost SPIKE_TIME
+    Spiked,
+    // landed on a pickup
+    PoweredUp,
+    // a shield took a wrong step
+    ShieldUsed,
+    // landed on a detour with its coin still there
+    CoinCollected,
+}
+
+fn is_replay(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::Replay
 }
 
 #[derive(Resource, Default)]
@@ -50,26 +149,35 @@ pub struct Game {
     pub background: Option<Entity>,
     pub score: Score,
     pub high_score: Score,
-    top_platform_loc: Location,
-    correct_path: Vec<Direction>,
-    platforms: Vec<Location>,
+    // the rules side of the run, everything else here is how it's drawn
+    pub climb: Climb,
     check_point: CheckPoint,
+    pub platforms: Platforms,
+    // which high score table the run counts towards
+    pub difficulty: Difficulty,
 }
 
 impl Game {
     pub fn reset(&mut self) {
         self.player = Player::default();
         self.score = Score::default();
-        self.top_platform_loc = Location::default();
-        self.correct_path = Vec::new();
-        self.platforms = Vec::new();
+        self.climb = Climb::default();
+        self.platforms = Platforms::default();
+    }
+
+    // where the player sprite is drawn, climb.player() is the platform they stand on
+    pub fn player_location(&self) -> &Location {
+        &self.player.location
     }
 
-    pub fn set_high_score(&mut self) {
+    // returns true when the high score was raised
+    pub fn set_high_score(&mut self) -> bool {
         if self.score.value > self.high_score.value
         {
             self.high_score.value = self.score.value;
+            return true;
         }
+        false
     }
 }
 
@@ -79,165 +187,359 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App){
         app
             .init_resource::<Game>()
+            .init_resource::<GameMode>()
+            .init_resource::<Recorder>()
+            .init_resource::<Playback>()
+            .init_resource::<PlatformCulling>()
+            .init_resource::<Motion>()
+            .init_resource::<CameraShake>()
+            .init_resource::<Curve>()
+            .add_event::<DirectionInput>()
+            .add_event::<GameEvent>()
+            .add_systems(Startup, (replay::load_playback, replay::start_launch_replay).chain())
+            .insert_resource(TowerSeed::new(crate::args::get("seed").and_then(|seed| seed.parse().ok())))
             .init_state::<PlayerAction>()
-            .add_systems(OnEnter(GameState::Playing), start_game)
+            .add_systems(OnEnter(GameState::Playing), (new_climb, start_game, replay::start_recording).chain())
+            .add_systems(OnEnter(GameState::GameOver), replay::store_recording)
             .add_systems(Update, (
                 update_camera,
                 update_background,
                 score::update_score,
                 check_point::update_display_checkpoint,
                 animation::execute_animations,
-                timer_check,
+                // a clock that ran out this frame beats a jump taken on it, live and played back
+                timer_check
+                    .after(check_point::update_display_checkpoint)
+                    .before(buffer_jump),
+                collapse_check,
+                platform::cull_platforms,
+                platform::crumble_platforms,
+                platform::move_platforms,
+                power_up::bob_pickups,
+                power_up::update_hud,
+                power_up::reveal_path,
+                coin::spin_coins,
+                coin::update_counter,
+                popup::float_popups,
             )
-                .run_if(in_state(GameState::Playing)))
+                .run_if(in_state(PlayState::Running)))
+            .add_systems(Update, track_high_score
+                    .run_if(resource_equals(GameMode::Classic))
+                    .run_if(in_state(PlayState::Running)))
             .add_systems(OnEnter(PlayerAction::Fall), player::set_fall_animation
                     .run_if(in_state(GameState::Playing)))
-            .add_systems(Update, handle_rest
-                    .run_if(in_state(PlayerAction::Rest))
-                    .run_if(in_state(GameState::Playing)))
-            .add_systems(Update, handle_jump
-                    .run_if(in_state(PlayerAction::Jump))
-                    .run_if(in_state(GameState::Playing)))
-            .add_systems(Update, handle_fall
+            .add_systems(Update, (
+                (
+                    input::read_keyboard,
+                    input::read_gamepad_buttons,
+                    input::read_gamepad_sticks,
+                    input::read_touches,
+                ).run_if(not(is_replay)),
+                replay::feed_playback.run_if(is_replay),
+                buffer_jump,
+                (
+                    advance_jump,
+                    start_jump,
+                ).run_if(in_state(PlayerAction::Jump)),
+            )
+                    .chain()
+                    // presses are timed on the clock after this frame's tick, live and played back
+                    .after(check_point::update_display_checkpoint)
+                    .run_if(not(in_state(PlayerAction::Fall)))
+                    .run_if(in_state(PlayState::Running)))
+            .add_systems(Update, (handle_fall, finish_fall)
+                    .chain()
                     .run_if(in_state(PlayerAction::Fall))
-                    .run_if(in_state(GameState::Playing)));
+                    .run_if(in_state(PlayState::Running)))
+            .add_plugins(pause::PausePlugin);
     }
 }
 
 fn start_game(
     mut commands: Commands,
-    mut asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     mut game: ResMut<Game>,
-    camera_query: Query<Entity, With<Camera2d>>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
+    mut camera_query: Query<(Entity, &mut Transform), With<Camera2d>>,
+    save: Res<SaveData>,
+    motion: Res<Motion>,
 ) 
 {
     // spawn the ground
-    commands.spawn(SpriteBundle {
-        texture: asset_server.load("ground_cloud.png"),
+    commands.spawn((SpriteBundle {
+        texture: assets.ground_cloud.clone(),
         transform: Transform::from_xyz(START_X, START_Y - GROUND_OFFSET, PLATFORM_Z),
         ..default()
-    });
+    }, StateScoped(GameState::Playing)));
 
-    player::spawn_player(&mut texture_atlases, &mut game, &mut commands, &mut asset_server);
-    game.high_score.init_high_score();
-    score::load_scores(&mut commands, &mut asset_server, &mut game);
-    platform::init_platforms(&mut commands, &mut asset_server, &mut game);
-    check_point::spawn_checkpoint(&mut commands, &asset_server, &mut game, texture_atlases); 
-    check_point::display_checkpoint_timer(&mut game, &mut asset_server, &mut commands);
+    player::spawn_player(&mut game, &mut commands, &assets);
+    let high_score = save.high_score(game.difficulty);
+    game.high_score.init_high_score(high_score);
+    score::load_scores(&mut commands, &assets, &mut game);
+    platform::init_platforms(&mut commands, &assets, &mut game);
+    check_point::spawn_checkpoint(&mut commands, &assets, &mut game);
+    check_point::display_checkpoint_timer(&mut game, &assets, &mut commands);
+    power_up::spawn_hud(&mut commands, &assets);
+    coin::spawn_counter(&mut commands, &assets);
 
-    // get the camera
-    for entity in camera_query.iter() {
+    // get the camera, starting where it would ease to
+    for (entity, mut transform) in camera_query.iter_mut() {
         game.camera = Some(entity);
+        *transform = Transform::from_xyz(0.0, camera_target(&game, &motion), CAMERA_Z);
     }
 }
 
-fn handle_rest(mut player_action: ResMut<NextState<PlayerAction>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+// daily runs are the same for everyone, so they don't take the settings' options
+fn new_climb(mut seed: ResMut<TowerSeed>,
+    mode: Res<GameMode>,
+    playback: Res<Playback>,
+    settings: Res<Settings>,
+    curve: Res<Curve>,
     mut game: ResMut<Game>,
+)
+{
+    game.difficulty = settings.difficulty;
+    let (config, curve) = match *mode {
+        GameMode::Classic => {
+            seed.new_run(None);
+            (settings.difficulty.config(&settings.climb), curve.0.clone())
+        },
+        GameMode::Daily(date) => {
+            seed.new_run(Some(date.seed()));
+            game.difficulty = Difficulty::Normal;
+            (ClimbConfig::default(), curve.0.clone())
+        },
+        // replays are played back on the curve they were recorded with,
+        // next to the high score of the difficulty they were played on
+        GameMode::Replay => {
+            seed.new_run(playback.seed());
+            match &playback.replay {
+                Some(replay) => {
+                    game.difficulty = replay.difficulty;
+                    (replay.config.clone(), replay.curve.clone())
+                },
+                None => (ClimbConfig::default(), curve.0.clone()),
+            }
+        },
+    };
+    game.climb = Climb::with_curve(seed.seed(), config, curve);
+}
+
+// the latest press wins, a press mid jump waits for the landing
+// presses are recorded as they arrive, so playback goes through the buffer the same way
+fn buffer_jump(mut player_action: ResMut<NextState<PlayerAction>>,
+    action: Res<State<PlayerAction>>,
+    mut inputs: EventReader<DirectionInput>,
+    mut game: ResMut<Game>,
+    mut recorder: ResMut<Recorder>,
 ) 
 {
+    if let Some(DirectionInput(direction)) = inputs.read().last() {
+        // a press that replaces one still waiting takes its place in the recording too
+        match game.player.buffered.replace(*direction) {
+            Some(_) => recorder.0.redirect(*direction),
+            None => recorder.0.record(*direction, &game.climb),
+        }
+        if *action.get() == PlayerAction::Rest {
+            player_action.set(PlayerAction::Jump);
+        }
+    }
+}
 
-    if keyboard_input.just_pressed(KeyCode::KeyA) ||
-    keyboard_input.just_pressed(KeyCode::ArrowLeft)
-    {
-        player_action.set(PlayerAction::Jump);
-        game.player.direction = Direction::Left;
+// moves the sprite along the arc, the climb already has the player on the next platform
+fn advance_jump(mut game: ResMut<Game>,
+    mut transforms: Query<&mut Transform>,
+    mut sprite: Query<&mut Sprite>,
+    motion: Res<Motion>,
+    time: Res<Time>,
+)
+{
+    let player = &mut game.player;
+    let Some(arc) = &mut player.arc else {
+        return;
+    };
+    let landed = arc.advance(time.delta_seconds(), motion.jump_time);
+    player.location = arc.location(motion.jump_time, motion.jump_height);
+    if landed {
+        player.arc = None;
     }
 
-    if keyboard_input.just_pressed(KeyCode::KeyD) ||
-    keyboard_input.just_pressed(KeyCode::ArrowRight)
-    {
-        player_action.set(PlayerAction::Jump);
-        game.player.direction = Direction::Right;
+    let entity = player.entity.unwrap();
+    if let Ok(mut sprite) = sprite.get_mut(entity) {
+        sprite.flip_x = player.direction == Direction::Right;
     }
+    *transforms.get_mut(entity).unwrap() = Transform::from_xyz(
+        player.location.x,
+        player.location.y,
+        PLAYER_Z,
+    );
 }
 
-fn handle_jump(mut player_action: ResMut<NextState<PlayerAction>>,
+// takes the buffered press once the player is on a platform, or goes back to resting
+fn start_jump(mut player_action: ResMut<NextState<PlayerAction>>,
     mut game: ResMut<Game>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     mut transforms: Query<&mut Transform>,
-    mut sprite: Query<&mut Sprite>,
+    mut events: EventWriter<GameEvent>,
 ) 
 {
-    let correct_dir = game.correct_path.remove(0);
-    let correct_loc = game.platforms.remove(0);
-    // fail early
-    if correct_dir != game.player.direction { // game over starts
-        // game over sound
-        commands.spawn(AudioBundle {
-            source: asset_server.load("woops.ogg"),
-            ..default()
-        });
-        player_action.set(PlayerAction::Fall);
+    let game = &mut *game;
+    if game.player.arc.is_some() {
+        return;
     }
-    else { // do jump
-        // sprite flip
-        *sprite.get_mut(game.player.entity.unwrap()).unwrap() = Sprite {
-            flip_x: match correct_dir {
-                Direction::Left => false,
-                Direction::Right => true,
-            },
-            ..default()
-        };
-
-        // check point
-        if correct_loc.y == game.check_point.location.y {
-            game.check_point.timer.reset();
-            // check point sound
-            commands.spawn(AudioBundle {
-                source: asset_server.load("impactGlass_heavy_002.ogg"),
-                ..default()
-            });
-            check_point::move_checkpoint(&mut game, &mut transforms);
+    if game.player.dead_end {
+        crumble_branch(game, &mut commands, &mut events);
+        if game.climb.lose_life() {
+            respawn(game, &mut events);
+            return;
         }
+        game.player.fall = Fall::default();
+        player_action.set(PlayerAction::Fall);
+        return;
+    }
+    let Some(direction) = game.player.buffered.take() else {
+        player_action.set(PlayerAction::Rest);
+        return;
+    };
+    game.player.direction = direction;
 
-        game.player.increment();
-        game.score.increment();
-        game.set_high_score();
-        platform::increment_platform(&mut commands, &asset_server, &mut game);
+    match game.climb.step(direction) {
+        Step::WrongDirection => {
+            events.send(GameEvent::WrongDirection);
+            if game.climb.lose_life() {
+                respawn(game, &mut events);
+                return;
+            }
+            // game over starts
+            game.player.fall = Fall::pushed(direction);
+            player_action.set(PlayerAction::Fall);
+        },
+        Step::Shielded => {
+            // a hop on the spot
+            events.send(GameEvent::ShieldUsed);
+            let here = game.player.location.clone();
+            game.player.arc = Some(JumpArc::new(here.clone(), here));
+        },
+        Step::Climbed { check_point, kind, pickup, points, time_bonus, .. } => {
+            // check point
+            if check_point {
+                events.send(GameEvent::CheckpointReached);
+                check_point::move_checkpoint(game, &mut transforms);
+            }
+            if kind == PlatformKind::Spiked {
+                events.send(GameEvent::Spiked);
+            }
+            if pickup.is_some() {
+                events.send(GameEvent::PoweredUp);
+                let left = Platform {
+                    kind,
+                    ..default()
+                };
+                platform::collect(&mut commands, &game.platforms, game.climb.player(), left);
+            }
 
-        // jump sound
-        commands.spawn(AudioBundle {
-            source: asset_server.load("footstep_wood_004.ogg"),
-            ..default()
-        });
+            game.score.value = game.climb.score();
+            let landing = player::standing_on(game.climb.player());
+            popup::spawn_popups(&mut commands, &assets, &landing, points, game.climb.combo().multiplier(), time_bonus);
+            platform::place_new_platforms(&mut commands, &assets, &mut game.platforms, &mut transforms, game.climb.tower());
 
-        // move the player
-        *transforms.get_mut(game.player.entity.unwrap()).unwrap() = Transform::from_xyz(
-            game.player.location.x,
-            game.player.location.y,
-            PLAYER_Z,
-        );
+            events.send(GameEvent::Jumped);
 
-        player_action.set(PlayerAction::Rest);       
+            // fly to the platform the climb has the player on now
+            game.player.arc = Some(JumpArc::new(game.player.location.clone(), landing));
+        },
+        Step::Detoured { location, coin } => {
+            events.send(GameEvent::Jumped);
+            if coin {
+                events.send(GameEvent::CoinCollected);
+                let left = Platform {
+                    detour: true,
+                    ..default()
+                };
+                platform::collect(&mut commands, &game.platforms, &location, left);
+            }
+            game.player.arc = Some(JumpArc::new(
+                game.player.location.clone(),
+                player::standing_on(&location),
+            ));
+        },
+        Step::Branched { location, dead_end } => {
+            events.send(GameEvent::Jumped);
+            game.player.dead_end = dead_end;
+            game.player.arc = Some(JumpArc::new(
+                game.player.location.clone(),
+                player::standing_on(&location),
+            ));
+        },
     }
 }
 
-fn handle_fall(mut game_state: ResMut<NextState<GameState>>,
-    mut player_action: ResMut<NextState<PlayerAction>>,
-    mut game: ResMut<Game>,
+// the player landed on the end of a decoy branch, it falls away with them on it
+fn crumble_branch(game: &mut Game,
+    commands: &mut Commands,
+    events: &mut EventWriter<GameEvent>,
+)
+{
+    game.player.dead_end = false;
+    game.player.buffered = None;
+    let branch = game.climb.crumble();
+    platform::crumble(commands, &mut game.platforms, &branch);
+    events.send(GameEvent::Crumbled);
+}
+
+// drops the player back to where the climb put them after a lost life,
+// platforms culled on the way up come back as the camera follows
+fn respawn(game: &mut Game, events: &mut EventWriter<GameEvent>) {
+    events.send(GameEvent::LifeLost);
+    game.score.value = game.climb.score();
+    game.player.buffered = None;
+    game.player.arc = Some(JumpArc::new(
+        game.player.location.clone(),
+        player::standing_on(game.climb.player()),
+    ));
+}
+
+fn handle_fall(mut game: ResMut<Game>,
     mut transforms: Query<&mut Transform>,
     time: Res<Time>,
+    motion: Res<Motion>,
+    mut shake: ResMut<CameraShake>,
+    mut events: EventWriter<GameEvent>,
 ) {
-    let gravity: f32 = 1000.;
     let ground_y = START_Y + player::PLAYER_OFFSET;
-
-    if game.player.location.y > ground_y {
-        game.player.location.y -= gravity * time.delta_seconds();
-        *transforms.get_mut(game.player.entity.unwrap()).unwrap() = Transform::from_xyz(
-            game.player.location.x,
-            game.player.location.y,
-            PLAYER_Z,
-        );
+    let player = &mut game.player;
+    let impact = player.fall.step(&mut player.location, time.delta_seconds(), ground_y, motion.bounce);
+    if let Some(speed) = impact {
+        events.send(GameEvent::Landed);
+        shake.start(speed * SHAKE_PER_SPEED);
     }
-    else {
+    *transforms.get_mut(player.entity.unwrap()).unwrap() = Transform::from_xyz(
+        player.location.x,
+        player.location.y,
+        PLAYER_Z,
+    );
+}
+
+// the game over screen waits for the player to come to rest and the shake to settle
+fn finish_fall(mut game_state: ResMut<NextState<GameState>>,
+    mut player_action: ResMut<NextState<PlayerAction>>,
+    game: Res<Game>,
+) {
+    if game.player.fall.landed.is_some_and(|landed| landed >= LANDED_TIME) {
         player_action.set(PlayerAction::Rest);
         do_game_over(&mut game_state);
     }
 }
 
+// daily runs keep their own record, see game_over::record_score
+fn track_high_score(mut game: ResMut<Game>,
+    mut save: ResMut<SaveData>,
+) {
+    if game.set_high_score() {
+        save.record_high_score(game.difficulty, game.high_score.value);
+    }
+}
+
 fn update_background(mut transforms: Query<&mut Transform>,
     game: ResMut<Game>,) {
     *transforms.get_mut(game.background.unwrap()).unwrap() = Transform::from_xyz(
@@ -247,25 +549,90 @@ fn update_background(mut transforms: Query<&mut Transform>,
     );
 }
 
+// where the camera heads after a lost life, the lowest it goes until the next hour glass
+pub(crate) fn respawn_camera_target(game: &Game, motion: &Motion) -> f32 {
+    player::standing_on(game.climb.respawn()).y + CAMERA_OFFSET + motion.look_ahead
+}
+
+// where the camera is headed, following where the player lands rather than the arc
+pub(crate) fn camera_target(game: &Game, motion: &Motion) -> f32 {
+    let landing = game.player.arc
+        .as_ref()
+        .map_or(game.player.location.y, |arc| arc.to().y);
+    landing + CAMERA_OFFSET + motion.look_ahead
+}
+
 fn update_camera(mut transforms: Query<&mut Transform>,
     game: ResMut<Game>,
+    motion: Res<Motion>,
+    mut shake: ResMut<CameraShake>,
+    time: Res<Time>,
 ) {
-    *transforms.get_mut(game.camera.unwrap()).unwrap() = Transform::from_xyz(
-        0.0,
-        game.player.location.y + 100.,
-        CAMERA_Z,
-    );
+    let mut transform = transforms.get_mut(game.camera.unwrap()).unwrap();
+    let target = camera_target(&game, &motion);
+    let ease = 1. - (-motion.camera_easing * time.delta_seconds()).exp();
+    // ease from where the camera would be without last frame's shake
+    let current = transform.translation.y - shake.offset().y;
+    let y = current + (target - current) * ease;
+    let offset = shake.update(time.delta_seconds());
+    *transform = Transform::from_xyz(offset.x, y + offset.y, CAMERA_Z);
 }
 
 fn do_game_over(game_state: &mut ResMut<NextState<GameState>>) {
     game_state.set(GameState::GameOver);
 }
 
-fn timer_check(game: ResMut<Game>,
-    mut player_action: ResMut<NextState<PlayerAction>>
+fn timer_check(mut game: ResMut<Game>,
+    action: Res<State<PlayerAction>>,
+    mut player_action: ResMut<NextState<PlayerAction>>,
+    mut events: EventWriter<GameEvent>,
 )
 {
-    if game.check_point.timer.finished() {
+    if game.climb.clock().expired() && *action.get() != PlayerAction::Fall {
+        events.send(GameEvent::TimerExpired);
+        if game.climb.lose_life() {
+            respawn(&mut game, &mut events);
+            player_action.set(PlayerAction::Jump);
+            return;
+        }
+        // falls from wherever it is in the arc
+        game.player.arc = None;
+        game.player.buffered = None;
+        game.player.fall = Fall::default();
         player_action.set(PlayerAction::Fall);
     }
 }
+
+// a crumbling platform gives way if the player is still on it, the cloud comes back
+// with a spare life since the player climbs over it again
+fn collapse_check(mut game: ResMut<Game>,
+    action: Res<State<PlayerAction>>,
+    mut player_action: ResMut<NextState<PlayerAction>>,
+    mut events: EventWriter<GameEvent>,
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut transforms: Query<&mut Transform>,
+)
+{
+    if !game.climb.collapsed() || *action.get() == PlayerAction::Fall {
+        return;
+    }
+    let game = &mut *game;
+    let platform = game.climb.collapse();
+    platform::crumble(&mut commands, &mut game.platforms, std::slice::from_ref(&platform));
+    events.send(GameEvent::Crumbled);
+    if game.climb.lose_life() {
+        let crumbling = Platform {
+            kind: PlatformKind::Crumbling,
+            ..default()
+        };
+        platform::restore_platform(&mut commands, &assets, &mut game.platforms, &mut transforms, &platform, crumbling);
+        respawn(game, &mut events);
+        player_action.set(PlayerAction::Jump);
+        return;
+    }
+    game.player.arc = None;
+    game.player.buffered = None;
+    game.player.fall = Fall::default();
+    player_action.set(PlayerAction::Fall);
+}
@@ -1,68 +1,63 @@
 use bevy::prelude::*;
-use std::time::Duration;
-use crate::game::{Location, Game};
+use crate::assets::GameAssets;
+use crate::GameState;
+use crate::game::Game;
 use crate::game::animation;
 
 const CHECK_POINT_SIZE: UVec2 = UVec2::splat(32);
 pub const CHECK_POINT_OFFSET: f32 = 20.;
-const CHECK_POINT_TIME: f32 = 20.;
 const CHECK_POINT_Z: f32 = 1.5;
 
 #[derive(Component)]
 pub struct CheckPointTimerEntity;
 
+// the hour glass sprite, where it is and its timer live in Game::climb
 #[derive(Component, Default)]
 pub struct CheckPoint {
-    pub timer: Timer,
-    pub location: Location,
     pub entity: Option<Entity>,
 }
 
-impl CheckPoint {
-    pub fn to_string(&self) -> String {
-        String::from("Time: ") + &(CHECK_POINT_TIME - self.timer.elapsed_secs().round()).to_string()
-    }
+fn timer_text(game: &Game) -> String {
+    format!("Time: {}", game.climb.clock().remaining().round())
 }
 
 
+// hour_glass.png is a 2x3 sheet
+pub fn atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(CHECK_POINT_SIZE, 2, 3, None, None)
+}
+
 pub fn spawn_checkpoint (
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
+    assets: &GameAssets,
     game: &mut ResMut<Game>,
-    mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
 ) {
-    let atlas_layout = texture_atlases.add(TextureAtlasLayout::from_grid(CHECK_POINT_SIZE, 2, 3, None, None));
     let animation_config = animation::get_checkpoint_animation_config();
-    let dur = Duration::from_secs_f32(CHECK_POINT_TIME);
-    game.check_point = CheckPoint {
-        timer: Timer::new(dur, TimerMode::Once),
-        location: game.top_platform_loc.clone(),
-        ..default()
-    };
-
+    let location = game.climb.check_point().clone();
     game.check_point.entity = Some(commands.spawn((
         SpriteBundle {
-            texture: asset_server.load("hour_glass.png"),
+            texture: assets.hour_glass.clone(),
             transform: Transform::from_xyz(
-                game.top_platform_loc.x,
-                game.top_platform_loc.y + CHECK_POINT_OFFSET,
+                location.x,
+                location.y + CHECK_POINT_OFFSET,
                 CHECK_POINT_Z,
             ),
             ..default()
         },
         TextureAtlas {
-            layout: atlas_layout,
+            layout: assets.hour_glass_layout.clone(),
             index: animation_config.first_sprite_index,
         },
         animation_config,
+        StateScoped(GameState::Playing),
     )).id());
 }
 
 pub fn display_checkpoint_timer(game: &mut ResMut<Game>,
-    asset_server: &mut Res<AssetServer>,
+    assets: &GameAssets,
     commands: &mut Commands) 
 {
-        commands.spawn(NodeBundle {
+        commands.spawn((NodeBundle {
             style: Style {
                 width: Val::Percent(100.),
                 height: Val::Percent(100.),
@@ -74,13 +69,13 @@ pub fn display_checkpoint_timer(game: &mut ResMut<Game>,
                 ..default()
             },
             ..default()
-            }
+            }, StateScoped(GameState::Playing))
         )
         .with_children(|parent|{
             parent.spawn((TextBundle::from_section(
-                game.check_point.to_string()
+                timer_text(game)
                 , TextStyle { 
-                    font: asset_server.load("FiraSans-Regular.ttf"),
+                    font: assets.font.clone(),
                     font_size: 40.,
                     color: Color::WHITE,
                 }
@@ -94,22 +89,20 @@ pub fn update_display_checkpoint(mut query: Query<&mut Text, With<CheckPointTime
     time: Res<Time>,
 ) 
 {
-    game.check_point.timer.tick(time.delta());
+    game.climb.tick(time.delta_seconds());
     for mut check_point in &mut query {
-        check_point.sections[0].value =  game.check_point.to_string();
+        check_point.sections[0].value =  timer_text(&game);
     }
 }
 
-pub fn move_checkpoint(game: &mut ResMut<Game>,
+pub fn move_checkpoint(game: &Game,
     transforms: &mut Query<&mut Transform>
 ) 
 {
-    game.check_point.timer.reset();
-    game.check_point.location = game.top_platform_loc.clone();
-    
+    let location = game.climb.check_point();
     *transforms.get_mut(game.check_point.entity.unwrap()).unwrap() = Transform::from_xyz(
-        game.top_platform_loc.x,
-        game.top_platform_loc.y + CHECK_POINT_OFFSET,
+        location.x,
+        location.y + CHECK_POINT_OFFSET,
         CHECK_POINT_Z,
     );
 
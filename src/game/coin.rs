@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::GameState;
+use crate::game::Game;
+
+// how high a coin sits above its cloud, and how fast it turns
+const COIN_OFFSET: f32 = 20.;
+const COIN_Z: f32 = 0.5;
+const SPIN_SPEED: f32 = 3.;
+const COUNTER_ICON: f32 = 24.;
+
+// the coin over a detour cloud, a child of the cloud's entity
+#[derive(Component)]
+pub struct Coin;
+
+#[derive(Component)]
+pub struct CoinCounter;
+
+pub fn spawn_coin(parent: &mut ChildBuilder, assets: &GameAssets) {
+    parent.spawn((SpriteBundle {
+        texture: assets.coin.clone(),
+        transform: Transform::from_xyz(0., COIN_OFFSET, COIN_Z),
+        ..default()
+    }, Coin));
+}
+
+// squashed side to side so it looks like it's turning
+pub fn spin_coins(mut coins: Query<&mut Transform, With<Coin>>,
+    time: Res<Time>,
+)
+{
+    let turn = (time.elapsed_seconds() * SPIN_SPEED).cos();
+    for mut transform in &mut coins {
+        transform.scale.x = turn;
+    }
+}
+
+// the coins picked up this run, bottom left
+pub fn spawn_counter(commands: &mut Commands,
+    assets: &GameAssets,
+)
+{
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Percent(1.),
+            left: Val::Percent(1.),
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(8.),
+            ..default()
+        },
+        ..default()
+        }, StateScoped(GameState::Playing))
+    )
+    .with_children(|parent|{
+        parent.spawn(ImageBundle {
+            style: Style {
+                width: Val::Px(COUNTER_ICON),
+                height: Val::Px(COUNTER_ICON),
+                ..default()
+            },
+            image: UiImage::new(assets.coin.clone()),
+            ..default()
+        });
+        parent.spawn((TextBundle::from_section(
+            "0"
+            , TextStyle {
+                font: assets.font.clone(),
+                font_size: 30.,
+                color: Color::WHITE,
+            }
+        ), CoinCounter));
+    });
+}
+
+pub fn update_counter(mut texts: Query<&mut Text, With<CoinCounter>>,
+    game: Res<Game>,
+)
+{
+    let label = game.climb.coins().to_string();
+    for mut text in &mut texts {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.clone();
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::asset::io::Reader;
+use crate::rules::DifficultyCurve;
+
+const CURVE_PATH: &str = "difficulty_curve.json";
+
+// the curve new runs are built with, the table in the assets folder as of the build
+// until CurveFilePlugin has read the file itself
+#[derive(Resource, Clone)]
+pub struct Curve(pub DifficultyCurve);
+
+impl Default for Curve {
+    fn default() -> Self {
+        let table = include_str!("../../assets/difficulty_curve.json");
+        let curve: DifficultyCurve = serde_json::from_str(table).expect("the shipped difficulty curve should parse");
+        curve.check().expect("the shipped difficulty curve should be usable");
+        Curve(curve)
+    }
+}
+
+// reads the curve from the assets folder at runtime, so the table can be tweaked without a rebuild
+// and with bevy's file_watcher feature changes apply to the next run straight away
+// it's added by the game binary rather than GamePlugin, the headless sim shares GamePlugin
+// and there the file would really load, swapping the curve under tests that set their own
+pub struct CurveFilePlugin;
+
+impl Plugin for CurveFilePlugin {
+    fn build(&self, app: &mut App){
+        app
+            .init_asset::<CurveAsset>()
+            .register_asset_loader(CurveLoader)
+            .add_systems(Startup, load_curve)
+            .add_systems(Update, apply_curve);
+    }
+}
+
+#[derive(Asset, TypePath)]
+pub struct CurveAsset(DifficultyCurve);
+
+#[derive(Resource)]
+struct CurveFile(Handle<CurveAsset>);
+
+#[derive(Default)]
+struct CurveLoader;
+
+impl AssetLoader for CurveLoader {
+    type Asset = CurveAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let curve: DifficultyCurve = serde_json::from_slice(&bytes)?;
+        curve.check()?;
+        Ok(CurveAsset(curve))
+    }
+}
+
+fn load_curve(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CurveFile(asset_server.load(CURVE_PATH)));
+}
+
+// a file that doesn't parse or fails the check is logged by the asset server and the last good curve stays
+fn apply_curve(mut events: EventReader<AssetEvent<CurveAsset>>,
+    file: Res<CurveFile>,
+    curves: Res<Assets<CurveAsset>>,
+    mut curve: ResMut<Curve>,
+)
+{
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&file.0) || event.is_modified(&file.0) {
+            if let Some(CurveAsset(loaded)) = curves.get(&file.0) {
+                curve.0 = loaded.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use bevy::utils::SystemTime;
+
+// a calendar day in UTC, everyone playing on the same day climbs the same tower
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UtcDate {
+    year: i64,
+    month: u32,
+    day: u32,
+}
+
+impl UtcDate {
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|dur| dur.as_secs())
+            .unwrap_or_default();
+        UtcDate::from_days((secs / 86_400) as i64)
+    }
+
+    // days since 1970-01-01 to a civil date
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        UtcDate { year, month, day }
+    }
+
+    pub fn seed(&self) -> u64 {
+        (self.year as u64) * 10_000 + (self.month as u64) * 100 + self.day as u64
+    }
+}
+
+impl std::fmt::Display for UtcDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
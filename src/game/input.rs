@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use bevy::input::gamepad::{GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType};
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+use crate::game::Direction;
+use crate::settings::Settings;
+
+// how far a stick has to be pushed to count as a press,
+// and how far back it has to come before it can press again
+const STICK_PRESS: f32 = 0.6;
+const STICK_RELEASE: f32 = 0.3;
+
+// every source of left/right choices sends one of these,
+// buffer_jump doesn't care where it came from, replays included
+#[derive(Event, Clone, Copy)]
+pub struct DirectionInput(pub Direction);
+
+pub fn read_keyboard(keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    mut inputs: EventWriter<DirectionInput>,
+)
+{
+    for direction in [Direction::Left, Direction::Right] {
+        if keyboard_input.any_just_pressed(*settings.bindings.keys(direction)) {
+            inputs.send(DirectionInput(direction));
+        }
+    }
+}
+
+// d-pad and shoulder buttons on any connected pad
+pub fn read_gamepad_buttons(buttons: Res<ButtonInput<GamepadButton>>,
+    mut inputs: EventWriter<DirectionInput>,
+)
+{
+    for button in buttons.get_just_pressed() {
+        match button.button_type {
+            GamepadButtonType::DPadLeft | GamepadButtonType::LeftTrigger => {
+                inputs.send(DirectionInput(Direction::Left));
+            },
+            GamepadButtonType::DPadRight | GamepadButtonType::RightTrigger => {
+                inputs.send(DirectionInput(Direction::Right));
+            },
+            _ => {},
+        }
+    }
+}
+
+// a flick of either stick counts once, the stick has to come back
+// towards the middle before it can send another jump
+pub fn read_gamepad_sticks(axes: Res<Axis<GamepadAxis>>,
+    mut held: Local<HashMap<GamepadAxis, bool>>,
+    mut inputs: EventWriter<DirectionInput>,
+)
+{
+    for axis in axes.devices() {
+        if !matches!(axis.axis_type, GamepadAxisType::LeftStickX | GamepadAxisType::RightStickX) {
+            continue;
+        }
+        let value = axes.get(*axis).unwrap_or_default();
+        let was_held = held.get(axis).copied().unwrap_or_default();
+        if !was_held && value.abs() >= STICK_PRESS {
+            inputs.send(DirectionInput(if value < 0. {Direction::Left} else {Direction::Right}));
+            held.insert(*axis, true);
+        }
+        else if was_held && value.abs() <= STICK_RELEASE {
+            held.insert(*axis, false);
+        }
+    }
+}
+
+// tapping the left or right half of the screen
+pub fn read_touches(touches: Res<Touches>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut inputs: EventWriter<DirectionInput>,
+)
+{
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    for touch in touches.iter_just_pressed() {
+        if touch.position().x < window.width() / 2. {
+            inputs.send(DirectionInput(Direction::Left));
+        }
+        else {
+            inputs.send(DirectionInput(Direction::Right));
+        }
+    }
+}
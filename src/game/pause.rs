@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use bevy::input::gamepad::{GamepadButton, GamepadButtonType};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::hierarchy::despawn_with_children_recursive;
+use bevy::ui::FocusPolicy;
+use bevy::window::WindowFocused;
+use crate::{game_over, GameState};
+use crate::game::{Game, PlayerAction};
+use crate::ui::{self, BACKGROUND, BLUE, PURPLE};
+
+// only exists while playing, gameplay systems run in Running
+#[derive(SubStates, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[source(GameState = GameState::Playing)]
+pub enum PlayState {
+    #[default]
+    Running,
+    Paused,
+}
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App){
+        app
+            .add_sub_state::<PlayState>()
+            .enable_state_scoped_entities::<PlayState>()
+            .add_systems(Update, (
+                toggle_pause,
+                pause_on_focus_lost,
+            )
+                .run_if(in_state(GameState::Playing)))
+            .add_systems(OnEnter(PlayState::Paused), load_pause_menu)
+            .add_systems(Update, button_system.run_if(in_state(PlayState::Paused)));
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum PauseButton {
+    Resume,
+    Restart,
+    Quit,
+}
+
+fn toggle_pause(keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    play_state: Res<State<PlayState>>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+)
+{
+    let pressed = keyboard_input.any_just_pressed([KeyCode::Escape, KeyCode::KeyP]) ||
+        gamepad_buttons.get_just_pressed().any(|button| button.button_type == GamepadButtonType::Start);
+    if !pressed {
+        return;
+    }
+    match play_state.get() {
+        PlayState::Running => next_play_state.set(PlayState::Paused),
+        PlayState::Paused => next_play_state.set(PlayState::Running),
+    }
+}
+
+fn pause_on_focus_lost(mut focus_events: EventReader<WindowFocused>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+)
+{
+    if focus_events.read().any(|event| !event.focused) {
+        next_play_state.set(PlayState::Paused);
+    }
+}
+
+fn load_pause_menu(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        // lighter than the other screens, so the frozen run shows through
+        background_color: BackgroundColor(BACKGROUND.with_alpha(0.8)),
+        focus_policy: FocusPolicy::Block,
+        z_index: ZIndex::Global(10),
+        ..default()
+    }, StateScoped(PlayState::Paused)))
+    .with_children(|parent|{
+        ui::spawn_text(parent, &assets, "PAUSED", 40.);
+        ui::spawn_button(parent, &assets, "RESUME", PauseButton::Resume, 300., PURPLE);
+        ui::spawn_button(parent, &assets, "RESTART", PauseButton::Restart, 300., PURPLE);
+        ui::spawn_button(parent, &assets, "QUIT TO MENU", PauseButton::Quit, 300., PURPLE);
+    });
+}
+
+fn button_system(mut commands: Commands,
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &PauseButton)>,
+    mut next_play_state: ResMut<NextState<PlayState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+)
+{
+    for (interaction, mut background_color, button) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = BackgroundColor(BLUE);
+                match button {
+                    PauseButton::Resume => next_play_state.set(PlayState::Running),
+                    PauseButton::Restart => commands.add(restart_run),
+                    PauseButton::Quit => {
+                        commands.add(end_run);
+                        game_state.set(GameState::Menu);
+                    },
+                }
+            },
+            Interaction::Hovered => *background_color = BackgroundColor(BLUE),
+            Interaction::None => *background_color = BackgroundColor(PURPLE),
+        }
+    }
+}
+
+// a run given up on still banks its coins and counts for the high score and today's best
+fn end_run(world: &mut World) {
+    world.run_system_once(game_over::record_score);
+}
+
+// what leaving Playing would despawn, without actually leaving it
+fn clear_run(world: &mut World) {
+    let entities: Vec<Entity> = world
+        .query::<(Entity, &StateScoped<GameState>)>()
+        .iter(world)
+        .filter(|(_, scope)| scope.0 == GameState::Playing)
+        .map(|(entity, _)| entity)
+        .collect();
+    for entity in entities {
+        despawn_with_children_recursive(world, entity);
+    }
+
+    world.resource_mut::<NextState<PlayerAction>>().set(PlayerAction::Rest);
+    let mut game = world.resource_mut::<Game>();
+    game.reset();
+    if let Some(camera) = game.camera {
+        if let Some(mut transform) = world.get_mut::<Transform>(camera) {
+            *transform = Transform::default();
+        }
+    }
+}
+
+// same state transitions don't run OnEnter, so start the run again by hand
+fn restart_run(world: &mut World) {
+    end_run(world);
+    clear_run(world);
+    world.run_schedule(OnEnter(GameState::Playing));
+    world.resource_mut::<NextState<PlayState>>().set(PlayState::Running);
+}
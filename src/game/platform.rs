@@ -1,59 +1,342 @@
 use bevy::prelude::*;
-use crate::game::{Direction, Game, Location};
-use crate::game::{Y_INC, X_INC, PLATFORM_Z};
+use std::collections::VecDeque;
+use crate::assets::GameAssets;
+use crate::{GameState, WINDOW_Y};
+use crate::game::{camera_target, respawn_camera_target, Game, Location, Motion};
+use crate::rules::{Drift, PlatformKind, PowerUp, Tower, CRUMBLE_DELAY};
+use crate::game::{coin, power_up};
+use bevy::ecs::system::EntityCommands;
+use crate::game::PLATFORM_Z;
 
-const RIGHT_BOUND: f32 = crate::WINDOW_X / 2. - 50.;
-const LEFT_BOUND: f32 = crate::WINDOW_X / 2. * -1. + 50.;
+// decoys are clouds off the correct_path, drawn a touch bluer for players who look closely
+// drift is set on drifting platforms, where they slide between
+#[derive(Component, Clone, PartialEq, Debug, Default)]
+pub struct Platform {
+    pub decoy: bool,
+    pub kind: PlatformKind,
+    pub drift: Option<Drift>,
+    // floating over it until the player lands there
+    pub pickup: Option<PowerUp>,
+    // off the path round the first of a zigzag, with a coin on it until it's collected
+    pub detour: bool,
+    pub coin: bool,
+}
+
+pub(crate) const DECOY_TINT: Color = Color::srgb(0.9, 0.92, 1.);
+// seconds for a crumbling cloud to fade out, and how fast it drops meanwhile
+const CRUMBLE_TIME: f32 = 0.5;
+const CRUMBLE_SPEED: f32 = 120.;
+// degrees a crumbling platform rocks by just before it goes
+const WOBBLE: f32 = 3.;
+const WOBBLE_SPEED: f32 = 40.;
 
+// a decoy cloud falling away, it goes back to the pool once it has faded
 #[derive(Component, Default)]
-pub struct Platform;
+pub struct Crumbling {
+    pub elapsed: f32,
+}
+
+// how far below the bottom of the view a platform goes before it's recycled
+#[derive(Resource)]
+pub struct PlatformCulling {
+    pub distance: f32,
+}
+
+impl Default for PlatformCulling {
+    fn default() -> Self {
+        PlatformCulling {
+            distance: 100.,
+        }
+    }
+}
+
+// the cloud sprites of a run, placed ones bottom first,
+// pooled ones are off screen waiting to be moved to a new spot
+// and culled is where the pooled ones were, top last, in case a lost life drops the view back down
+#[derive(Default)]
+pub struct Platforms {
+    placed: VecDeque<(Location, Entity)>,
+    pool: Vec<Entity>,
+    culled: Vec<(Location, Platform)>,
+    // the highest cloud so far, the tower only grows above it
+    top: Option<f32>,
+}
+
+impl Platforms {
+    pub fn placed(&self) -> impl Iterator<Item = &(Location, Entity)> {
+        self.placed.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.placed.len() + self.pool.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn culled(&self) -> impl Iterator<Item = &Location> {
+        self.culled.iter().map(|(loc, _)| loc)
+    }
+}
 
 pub fn init_platforms(commands: &mut Commands,
-    asset_server: &mut Res<AssetServer>,
-    game: &mut ResMut<Game>
+    assets: &GameAssets,
+    game: &mut Game,
 )
 {
-    for _i in 0..30 {
-        increment_platform(commands, &asset_server, game);  
+    let tower = game.climb.tower();
+    for index in 0..tower.platforms().len() {
+        for (loc, platform) in rung_clouds(tower, index) {
+            let entity = spawn_platform(commands, assets, loc, platform);
+            game.platforms.placed.push_back((loc.clone(), entity));
+        }
+        game.platforms.top = Some(tower.platforms()[index].y);
     }
 }
 
-pub fn increment_platform(commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
-    game: &mut ResMut<Game>,
+// puts clouds on whatever the tower grew since the last call, reusing pooled ones
+pub fn place_new_platforms(commands: &mut Commands,
+    assets: &GameAssets,
+    platforms: &mut Platforms,
+    transforms: &mut Query<&mut Transform>,
+    tower: &Tower,
 )
 {
-    let mut dir = gen_rand_dir();
-    out_of_bounds(&game.top_platform_loc, &mut dir);
-    increment_loc(&mut game.top_platform_loc, &dir);
-    game.correct_path.push(dir);
-    let new_platform_loc = game.top_platform_loc.clone();
-    game.platforms.push(new_platform_loc);
-    commands.spawn(SpriteBundle {
-        texture: asset_server.load("cloud.png"),
-        transform: Transform::from_xyz(game.top_platform_loc.x, game.top_platform_loc.y, PLATFORM_Z),
+    for (index, rung) in tower.platforms().iter().enumerate() {
+        if platforms.top.is_some_and(|top| rung.y <= top) {
+            continue;
+        }
+        for (loc, platform) in rung_clouds(tower, index) {
+            let entity = take_platform(commands, assets, platforms, transforms, loc, platform);
+            platforms.placed.push_back((loc.clone(), entity));
+        }
+        platforms.top = Some(rung.y);
+    }
+}
+
+// a rung's platform with the detour round the one below it, which is only known
+// once this rung has grown, followed by the decoys branching off beside it
+fn rung_clouds(tower: &Tower,
+    index: usize,
+) -> impl Iterator<Item = (&Location, Platform)>
+{
+    let platform = Platform {
+        decoy: false,
+        kind: tower.kinds()[index],
+        drift: tower.drift(index),
+        pickup: tower.pickups()[index],
         ..default()
-    });
+    };
+    let below = index.checked_sub(1).and_then(|below| tower.detours()[below].as_ref());
+    let detour = below.map(|detour| (&detour.location, Platform {
+        detour: true,
+        coin: detour.coin,
+        ..default()
+    }));
+    let decoy = Platform {
+        decoy: true,
+        ..default()
+    };
+    detour.into_iter()
+        .chain(std::iter::once((&tower.platforms()[index], platform)))
+        .chain(tower.branches()[index].iter().map(move |loc| (loc, decoy.clone())))
+}
+
+// puts a cloud back that crumbled under a platform the player can come back to
+pub fn restore_platform(commands: &mut Commands,
+    assets: &GameAssets,
+    platforms: &mut Platforms,
+    transforms: &mut Query<&mut Transform>,
+    loc: &Location,
+    platform: Platform,
+)
+{
+    let entity = take_platform(commands, assets, platforms, transforms, loc, platform);
+    let at = platforms.placed.partition_point(|(placed, _)| placed.y <= loc.y);
+    platforms.placed.insert(at, (loc.clone(), entity));
 }
 
-fn increment_loc(loc: &mut Location, dir: &Direction) {
-    match dir {
-        Direction::Left => {loc.x -= X_INC;},
-        Direction::Right => {loc.x += X_INC;},
+fn take_platform(commands: &mut Commands,
+    assets: &GameAssets,
+    platforms: &mut Platforms,
+    transforms: &mut Query<&mut Transform>,
+    loc: &Location,
+    platform: Platform,
+) -> Entity
+{
+    match platforms.pool.pop() {
+        Some(entity) => {
+            if let Ok(mut transform) = transforms.get_mut(entity) {
+                *transform = Transform::from_xyz(loc.x, loc.y, PLATFORM_Z);
+            }
+            // pooled clouds may have crumbled or been a different kind
+            let mut cloud = commands.entity(entity);
+            cloud.despawn_descendants();
+            decorate(&mut cloud, assets, &platform);
+            cloud.insert((platform_sprite(&platform), platform_texture(assets, &platform), platform, Visibility::Inherited))
+                .remove::<Crumbling>();
+            entity
+        },
+        None => spawn_platform(commands, assets, loc, platform),
     }
-    loc.y += Y_INC;
 }
 
-fn gen_rand_dir() -> Direction {
-    if rand::random() {Direction::Right}
-    else {Direction::Left}
+fn spawn_platform(commands: &mut Commands,
+    assets: &GameAssets,
+    loc: &Location,
+    platform: Platform,
+) -> Entity
+{
+    let mut cloud = commands.spawn((SpriteBundle {
+        sprite: platform_sprite(&platform),
+        texture: platform_texture(assets, &platform),
+        transform: Transform::from_xyz(loc.x, loc.y, PLATFORM_Z),
+        ..default()
+    }, StateScoped(GameState::Playing)));
+    decorate(&mut cloud, assets, &platform);
+    cloud.insert(platform).id()
 }
 
-fn out_of_bounds(loc: &Location, dir: &mut Direction) {
-    if loc.x + X_INC > RIGHT_BOUND {
-        *dir = Direction::Left;
+// whatever floats over the cloud
+fn decorate(cloud: &mut EntityCommands, assets: &GameAssets, platform: &Platform) {
+    if let Some(power_up) = platform.pickup {
+        cloud.with_children(|parent| power_up::spawn_pickup(parent, assets, power_up));
+    }
+    if platform.coin {
+        cloud.with_children(|parent| coin::spawn_coin(parent, assets));
+    }
+}
+
+// the player landed on a pickup or coin, it goes from over the cloud which is left as platform
+pub fn collect(commands: &mut Commands,
+    platforms: &Platforms,
+    loc: &Location,
+    platform: Platform,
+)
+{
+    let Some((_, entity)) = platforms.placed.iter().find(|(placed, _)| placed == loc) else {
+        return;
+    };
+    commands.entity(*entity)
+        .despawn_descendants()
+        .insert(platform);
+}
+
+fn platform_sprite(platform: &Platform) -> Sprite {
+    Sprite {
+        color: if platform.decoy {DECOY_TINT} else {Color::WHITE},
+        ..default()
+    }
+}
+
+fn platform_texture(assets: &GameAssets, platform: &Platform) -> Handle<Image> {
+    match platform.kind {
+        PlatformKind::Normal => assets.cloud.clone(),
+        PlatformKind::Crumbling => assets.crumbling_cloud.clone(),
+        PlatformKind::Drifting => assets.drifting_cloud.clone(),
+        PlatformKind::Spiked => assets.spiked_cloud.clone(),
+    }
+}
+
+// drifting clouds ahead slide about, the one the player lands on stays where they landed
+// and the crumbling one under the player shakes harder as it's about to go
+pub fn move_platforms(mut platforms: Query<(&Platform, &mut Transform), Without<Crumbling>>,
+    game: Res<Game>,
+)
+{
+    let climb = &game.climb;
+    let player = climb.player();
+    for (platform, mut transform) in &mut platforms {
+        if let Some(drift) = &platform.drift {
+            if drift.home.y > player.y {
+                transform.translation.x = drift.at(climb.time()).x;
+            }
+            else if drift.home.y == player.y && !climb.on_branch() {
+                transform.translation.x = player.x;
+            }
+        }
+        if platform.kind == PlatformKind::Crumbling {
+            let home = transform.translation.y;
+            let shake = match climb.crumbling() {
+                Some(left) if home == player.y && !climb.on_branch() => {
+                    let closeness = 1. - (left / CRUMBLE_DELAY).clamp(0., 1.);
+                    WOBBLE * closeness * (climb.time() * WOBBLE_SPEED).sin()
+                },
+                _ => 0.,
+            };
+            transform.rotation = Quat::from_rotation_z(shake.to_radians());
+        }
+    }
+}
+
+// starts the clouds of a crumbled branch falling, they're no longer placed
+pub fn crumble(commands: &mut Commands,
+    platforms: &mut Platforms,
+    branch: &[Location],
+)
+{
+    platforms.placed.retain(|(loc, entity)| {
+        let crumbled = branch.contains(loc);
+        if crumbled {
+            commands.entity(*entity).insert(Crumbling::default());
+        }
+        !crumbled
+    });
+}
+
+pub fn crumble_platforms(mut commands: Commands,
+    mut game: ResMut<Game>,
+    mut crumbling: Query<(Entity, &mut Crumbling, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+)
+{
+    let secs = time.delta_seconds();
+    for (entity, mut crumble, mut transform, mut sprite) in &mut crumbling {
+        crumble.elapsed += secs;
+        transform.translation.y -= CRUMBLE_SPEED * secs;
+        sprite.color.set_alpha((1. - crumble.elapsed / CRUMBLE_TIME).max(0.));
+        if crumble.elapsed >= CRUMBLE_TIME {
+            commands.entity(entity)
+                .insert(Visibility::Hidden)
+                .remove::<Crumbling>();
+            game.platforms.pool.push(entity);
+        }
+    }
+}
+
+// a lost life sends the camera back down, so what it's heading to counts as in view too
+pub fn cull_platforms(mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut game: ResMut<Game>,
+    mut transforms: Query<&mut Transform>,
+    details: Query<&Platform>,
+    culling: Res<PlatformCulling>,
+    motion: Res<Motion>,
+)
+{
+    let Some(camera) = game.camera.and_then(|camera| transforms.get(camera).ok()) else {
+        return;
+    };
+    let view = camera.translation.y.min(camera_target(&game, &motion));
+    let cull_below = view - WINDOW_Y / 2. - culling.distance;
+    // nothing brings the view lower than a respawn at the last hour glass, what's culled below that is gone for good
+    let never_back = respawn_camera_target(&game, &motion) - WINDOW_Y / 2. - culling.distance;
+    let platforms = &mut game.platforms;
+    let gone = platforms.culled.partition_point(|(loc, _)| loc.y < never_back);
+    platforms.culled.drain(..gone);
+    while platforms.culled.last().is_some_and(|(loc, _)| loc.y >= cull_below) {
+        let (loc, platform) = platforms.culled.pop().unwrap();
+        let entity = take_platform(&mut commands, &assets, platforms, &mut transforms, &loc, platform);
+        platforms.placed.push_front((loc, entity));
     }
-    if loc.x - X_INC < LEFT_BOUND {
-        *dir = Direction::Right;
+    while let Some((loc, entity)) = platforms.placed.front() {
+        if loc.y >= cull_below {
+            break;
+        }
+        let platform = details.get(*entity).cloned().unwrap_or_default();
+        platforms.pool.push(*entity);
+        let (loc, _) = platforms.placed.pop_front().unwrap();
+        platforms.culled.push((loc, platform));
     }
 }
@@ -1,10 +1,8 @@
-// use std::thread::sleep;
-// use std::time::Duration;
-
 use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::GameState;
 use crate::game::{Direction, Game, Location};
 use crate::game::animation::AnimationConfig;
-use crate::game::{Y_INC, X_INC, START_X, START_Y};
 
 use super::animation::{get_rest_animation_config, get_fall_animation_config};
 
@@ -17,6 +15,106 @@ pub struct Player {
     pub entity: Option<Entity>,
     pub location: Location,
     pub direction: Direction,
+    // the jump in the air, the climb has already moved on to where it lands
+    pub arc: Option<JumpArc>,
+    // pressed mid jump, taken as soon as the player lands
+    pub buffered: Option<Direction>,
+    pub fall: Fall,
+    // headed for the last cloud of a decoy branch, it crumbles on landing
+    pub dead_end: bool,
+}
+
+const GRAVITY: f32 = 1800.;
+// sideways speed carried off a platform by a wrong step
+const FALL_PUSH: f32 = 120.;
+// the share of speed kept by a bounce, and the slowest landing that still bounces
+const BOUNCINESS: f32 = 0.45;
+const MIN_BOUNCE_SPEED: f32 = 150.;
+// how far the player can drift sideways before hitting the edge of the view
+const FALL_BOUND: f32 = crate::WINDOW_X / 2. - 32.;
+
+// a fall down to the ground cloud, velocity is in units per second
+#[derive(Clone, Debug, Default)]
+pub struct Fall {
+    pub velocity: Vec2,
+    // seconds since coming to rest on the ground
+    pub landed: Option<f32>,
+}
+
+impl Fall {
+    // stepping the wrong way carries the player off in that direction
+    pub fn pushed(direction: Direction) -> Self {
+        let x = match direction {
+            Direction::Left => -FALL_PUSH,
+            Direction::Right => FALL_PUSH,
+        };
+        Fall {
+            velocity: Vec2::new(x, 0.),
+            landed: None,
+        }
+    }
+
+    // moves the player by secs of gravity, returns the speed of any impact with the ground
+    pub fn step(&mut self, location: &mut Location, secs: f32, ground_y: f32, bounce: bool) -> Option<f32> {
+        if let Some(landed) = &mut self.landed {
+            *landed += secs;
+            return None;
+        }
+        self.velocity.y -= GRAVITY * secs;
+        location.x = (location.x + self.velocity.x * secs).clamp(-FALL_BOUND, FALL_BOUND);
+        location.y += self.velocity.y * secs;
+        if location.y > ground_y {
+            return None;
+        }
+
+        location.y = ground_y;
+        let impact = -self.velocity.y;
+        if bounce && impact > MIN_BOUNCE_SPEED {
+            self.velocity = Vec2::new(self.velocity.x * BOUNCINESS, impact * BOUNCINESS);
+        }
+        else {
+            self.velocity = Vec2::ZERO;
+            self.landed = Some(0.);
+        }
+        Some(impact)
+    }
+}
+
+// a parabola from one platform to the next
+#[derive(Clone, Debug)]
+pub struct JumpArc {
+    from: Location,
+    to: Location,
+    elapsed: f32,
+}
+
+impl JumpArc {
+    pub fn new(from: Location, to: Location) -> Self {
+        JumpArc {
+            from,
+            to,
+            elapsed: 0.,
+        }
+    }
+
+    // moves along by secs, true once landed
+    pub fn advance(&mut self, secs: f32, duration: f32) -> bool {
+        self.elapsed = (self.elapsed + secs).min(duration);
+        self.elapsed >= duration
+    }
+
+    // where the player is drawn, height is how far the arc rises above a straight line
+    pub fn location(&self, duration: f32, height: f32) -> Location {
+        let t = if duration > 0. {self.elapsed / duration} else {1.};
+        Location {
+            x: self.from.x + (self.to.x - self.from.x) * t,
+            y: self.from.y + (self.to.y - self.from.y) * t + 4. * height * t * (1. - t),
+        }
+    }
+
+    pub fn to(&self) -> &Location {
+        &self.to
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
@@ -29,39 +127,38 @@ pub enum PlayerAction {
 
 
 impl Player {
+    // stand on the given platform
+    pub fn place(&mut self, platform: &Location) {
+        self.location = standing_on(platform);
+    }
+}
 
-    pub fn increment(&mut self) {
-        match self.direction {
-            Direction::Left => 
-            {
-                self.location.x -= X_INC;
-            },
-            Direction::Right =>
-            {
-                self.location.x += X_INC;
-            },
-        }
-        self.location.y += Y_INC;
+// where the player is drawn when on the given platform
+pub fn standing_on(platform: &Location) -> Location {
+    Location {
+        x: platform.x,
+        y: platform.y + PLAYER_OFFSET,
     }
 }
 
-pub fn spawn_player(texture_atlases: &mut ResMut<Assets<TextureAtlasLayout>>,
-    game: &mut ResMut<Game>,
+// ball_guy.png is a 4x2 sheet
+pub fn atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(PLAYER_SIZE, 4, 2, None, None)
+}
+
+pub fn spawn_player(game: &mut ResMut<Game>,
     commands: &mut Commands,
-    asset_server: &mut Res<AssetServer>,
+    assets: &GameAssets,
 ) {
 
-    let atlas_layout = texture_atlases.add(TextureAtlasLayout::from_grid(PLAYER_SIZE, 4, 2, None, None));
     let animation_config = get_rest_animation_config();
 
-    game.player.location = Location {
-        x: START_X,
-        y: START_Y + PLAYER_OFFSET,
-    };
+    let game = &mut **game;
+    game.player.place(game.climb.player());
 
     game.player.entity = Some(commands.spawn((
         SpriteBundle {
-            texture: asset_server.load("ball_guy.png"),
+            texture: assets.player.clone(),
             transform: Transform::from_xyz(
                 game.player.location.x,
                 game.player.location.y,
@@ -70,10 +167,11 @@ pub fn spawn_player(texture_atlases: &mut ResMut<Assets<TextureAtlasLayout>>,
             ..default()
         },
         TextureAtlas {
-            layout: atlas_layout,
+            layout: assets.player_layout.clone(),
             index: animation_config.first_sprite_index,
         },
         animation_config,
+        StateScoped(GameState::Playing),
     )).id());
 }
 
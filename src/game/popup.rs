@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::GameState;
+use crate::game::Location;
+
+// how long a popup floats for, how far it rises, and where it starts over the player
+const POPUP_TIME: f32 = 0.8;
+const POPUP_RISE: f32 = 60.;
+const POPUP_OFFSET: f32 = 40.;
+// a time bonus shows above the points for the same jump
+const POPUP_SPACING: f32 = 28.;
+const POPUP_Z: f32 = 5.;
+const COMBO_COLOR: Color = Color::srgb(1., 0.85, 0.3);
+const TIME_COLOR: Color = Color::srgb(0.6, 0.9, 1.);
+
+// points scored floating up from where they were scored, then fading out
+#[derive(Component)]
+pub struct ScorePopup {
+    age: f32,
+    start_y: f32,
+}
+
+// +1 for a plain jump, +6 x3 with a combo going
+pub fn points_label(points: i32, multiplier: i32) -> String {
+    if multiplier > 1 {
+        format!("+{} x{}", points, multiplier)
+    }
+    else {
+        format!("+{}", points)
+    }
+}
+
+pub fn time_label(bonus: i32) -> String {
+    format!("+{} TIME", bonus)
+}
+
+// the points for a jump over the platform it lands on, and any time bonus over those
+pub fn spawn_popups(commands: &mut Commands,
+    assets: &GameAssets,
+    at: &Location,
+    points: i32,
+    multiplier: i32,
+    time_bonus: i32,
+)
+{
+    let color = if multiplier > 1 {COMBO_COLOR} else {Color::WHITE};
+    spawn_popup(commands, assets, at, 0., points_label(points, multiplier), color);
+    if time_bonus > 0 {
+        spawn_popup(commands, assets, at, POPUP_SPACING, time_label(time_bonus), TIME_COLOR);
+    }
+}
+
+fn spawn_popup(commands: &mut Commands,
+    assets: &GameAssets,
+    at: &Location,
+    above: f32,
+    label: String,
+    color: Color,
+)
+{
+    let start_y = at.y + POPUP_OFFSET + above;
+    commands.spawn((Text2dBundle {
+        text: Text::from_section(
+            label
+            , TextStyle {
+                font: assets.font.clone(),
+                font_size: 25.,
+                color,
+            }
+        ),
+        transform: Transform::from_xyz(at.x, start_y, POPUP_Z),
+        ..default()
+    },
+    ScorePopup { age: 0., start_y },
+    StateScoped(GameState::Playing)));
+}
+
+pub fn float_popups(mut commands: Commands,
+    mut popups: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+    time: Res<Time>,
+)
+{
+    for (entity, mut popup, mut transform, mut text) in &mut popups {
+        popup.age += time.delta_seconds();
+        if popup.age >= POPUP_TIME {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let along = popup.age / POPUP_TIME;
+        transform.translation.y = popup.start_y + POPUP_RISE * along;
+        for section in &mut text.sections {
+            section.style.color.set_alpha(1. - along);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::GameState;
+use crate::game::Game;
+use crate::game::platform::{Crumbling, Platform, DECOY_TINT};
+use crate::rules::PowerUp;
+
+const ICON_SIZE: UVec2 = UVec2::splat(20);
+// how high a pickup floats above its cloud, and how far it bobs
+const PICKUP_OFFSET: f32 = 22.;
+const PICKUP_BOB: f32 = 3.;
+const PICKUP_Z: f32 = 0.5;
+const HUD_ICON: f32 = 28.;
+// decoys all but vanish while the path is revealed, and the path glows
+const REVEALED_DECOY_ALPHA: f32 = 0.25;
+const REVEAL_TINT: Color = Color::srgb(1., 0.95, 0.6);
+
+// the floating icon over a platform, a child of the platform's entity
+#[derive(Component)]
+pub struct Pickup;
+
+// one row of the active power ups on the hud
+#[derive(Component)]
+pub struct PowerUpHud(pub PowerUp);
+
+#[derive(Component)]
+pub struct PowerUpHudText(pub PowerUp);
+
+// power_ups.png is a 4x1 strip in PowerUp::ALL order
+pub fn atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(ICON_SIZE, 4, 1, None, None)
+}
+
+pub fn spawn_pickup(parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    power_up: PowerUp,
+)
+{
+    parent.spawn((SpriteBundle {
+        texture: assets.power_ups.clone(),
+        transform: Transform::from_xyz(0., PICKUP_OFFSET, PICKUP_Z),
+        ..default()
+    },
+    TextureAtlas {
+        layout: assets.power_ups_layout.clone(),
+        index: power_up.index(),
+    },
+    Pickup));
+}
+
+pub fn bob_pickups(mut pickups: Query<&mut Transform, With<Pickup>>,
+    time: Res<Time>,
+)
+{
+    let bob = (time.elapsed_seconds() * 4.).sin() * PICKUP_BOB;
+    for mut transform in &mut pickups {
+        transform.translation.y = PICKUP_OFFSET + bob;
+    }
+}
+
+// under the hour glass timer, a row per power up shown while it's running
+pub fn spawn_hud(commands: &mut Commands,
+    assets: &GameAssets,
+)
+{
+    commands.spawn((NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.),
+            right: Val::Percent(1.),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(5.),
+            ..default()
+        },
+        ..default()
+        }, StateScoped(GameState::Playing))
+    )
+    .with_children(|parent|{
+        for power_up in PowerUp::ALL {
+            parent.spawn((NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            }, PowerUpHud(power_up)))
+            .with_children(|parent|{
+                parent.spawn((TextBundle::from_section(
+                    ""
+                    , TextStyle {
+                        font: assets.font.clone(),
+                        font_size: 25.,
+                        color: Color::WHITE,
+                    }
+                ), PowerUpHudText(power_up)));
+                parent.spawn((ImageBundle {
+                    style: Style {
+                        width: Val::Px(HUD_ICON),
+                        height: Val::Px(HUD_ICON),
+                        ..default()
+                    },
+                    image: UiImage::new(assets.power_ups.clone()),
+                    ..default()
+                },
+                TextureAtlas {
+                    layout: assets.power_ups_layout.clone(),
+                    index: power_up.index(),
+                }));
+            });
+        }
+    });
+}
+
+pub fn update_hud(mut rows: Query<(&mut Style, &PowerUpHud)>,
+    mut texts: Query<(&mut Text, &PowerUpHudText)>,
+    game: Res<Game>,
+)
+{
+    let effects = game.climb.effects();
+    for (mut style, PowerUpHud(power_up)) in &mut rows {
+        let display = if effects.active(*power_up) {Display::Flex} else {Display::None};
+        if style.display != display {
+            style.display = display;
+        }
+    }
+    for (mut text, PowerUpHudText(power_up)) in &mut texts {
+        let label = effects.time_left(*power_up)
+            .map_or_else(String::new, |left| format!("{}s", left.ceil()));
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+
+// while the path is revealed decoys fade out and the clouds ahead that matter glow
+pub fn reveal_path(mut platforms: Query<(&Platform, &mut Sprite), Without<Crumbling>>,
+    game: Res<Game>,
+)
+{
+    let revealed = game.climb.effects().active(PowerUp::Reveal);
+    for (platform, mut sprite) in &mut platforms {
+        // detours are off the path but no trap, they're left as they are
+        let color = match (platform.decoy, revealed) {
+            (true, true) => DECOY_TINT.with_alpha(REVEALED_DECOY_ALPHA),
+            (true, false) => DECOY_TINT,
+            (false, true) if !platform.detour => REVEAL_TINT,
+            (false, _) => Color::WHITE,
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
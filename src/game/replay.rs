@@ -0,0 +1,354 @@
+use bevy::prelude::*;
+use crate::game::{Direction, Game, GameMode};
+use crate::rules::{Climb, ClimbConfig, CurveRow, Difficulty, DifficultyCurve, HazardWeights};
+use crate::game::input::DirectionInput;
+use crate::game::seed::TowerSeed;
+use crate::save::Storage;
+
+const REPLAY_VERSION: u32 = 9;
+const LAST_REPLAY_NAME: &str = "last_run.replay";
+
+// one accepted jump, timed from the start of the checkpoint it happened in,
+// and after how many lost lives, as each one starts the clock over
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReplayInput {
+    pub direction: Direction,
+    pub checkpoint: u32,
+    pub elapsed: f32,
+    pub lives_lost: u32,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Replay {
+    pub seed: u64,
+    // which high score table the run was up against
+    pub difficulty: Difficulty,
+    pub config: ClimbConfig,
+    pub curve: DifficultyCurve,
+    pub inputs: Vec<ReplayInput>,
+}
+
+// the file is plain text, a header, the seed, the difficulty, the climb options, then one line per jump:
+//
+// uppy-up replay 9
+// seed 1234
+// difficulty Normal
+// lives 3
+// check_point_time 20
+// check_point_every 30
+// lookahead 30
+// decoys false
+// branch_chance 0.3
+// branch_depth 2
+// hazards false
+// hazard_weights 12 2 2 2
+// pickup_chance 0.08
+// coin_chance 0.15
+// combo_window 0.6
+// time_bonus 1
+// curve 0 1 0 false false
+// curve 60 0.9 5 false false
+// L 0 0.512 0
+// R 1 3.25 1
+impl std::fmt::Display for Replay {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "uppy-up replay {}", REPLAY_VERSION)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "difficulty {}", self.difficulty.name())?;
+        let config = &self.config;
+        writeln!(f, "lives {}", config.lives)?;
+        writeln!(f, "check_point_time {}", config.check_point_time)?;
+        writeln!(f, "check_point_every {}", config.check_point_every)?;
+        writeln!(f, "lookahead {}", config.lookahead)?;
+        writeln!(f, "decoys {}", config.decoys)?;
+        writeln!(f, "branch_chance {}", config.branch_chance)?;
+        writeln!(f, "branch_depth {}", config.branch_depth)?;
+        writeln!(f, "hazards {}", config.hazards)?;
+        let weights = &config.hazard_weights;
+        writeln!(f, "hazard_weights {} {} {} {}", weights.normal, weights.crumbling, weights.drifting, weights.spiked)?;
+        writeln!(f, "pickup_chance {}", config.pickup_chance)?;
+        writeln!(f, "coin_chance {}", config.coin_chance)?;
+        writeln!(f, "combo_window {}", config.combo_window)?;
+        writeln!(f, "time_bonus {}", config.time_bonus)?;
+        for row in &self.curve.0 {
+            writeln!(f, "curve {} {} {} {} {}", row.score, row.time_scale, row.extra_spacing, row.decoys, row.hazards)?;
+        }
+        for input in &self.inputs {
+            let dir = match input.direction {
+                Direction::Left => "L",
+                Direction::Right => "R",
+            };
+            writeln!(f, "{} {} {} {}", dir, input.checkpoint, input.elapsed, input.lives_lost)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Replay {
+    type Err = String;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let mut lines = contents.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        let version = lines.next()
+            .and_then(|line| line.strip_prefix("uppy-up replay "))
+            .ok_or("missing replay header")?;
+        let version: u32 = version.parse().map_err(|err| format!("bad version: {err}"))?;
+        if version > REPLAY_VERSION {
+            return Err(format!("replay version {} is newer than {}", version, REPLAY_VERSION));
+        }
+
+        let seed = lines.next()
+            .and_then(|line| line.strip_prefix("seed "))
+            .ok_or("missing replay seed")?
+            .parse()
+            .map_err(|err| format!("bad seed: {err}"))?;
+
+        // version 1 replays have no options and were played with the defaults on a flat curve
+        let mut config = ClimbConfig::default();
+        // and replays from before power ups, coins or bonus points had none
+        if version < 5 {
+            config.pickup_chance = 0.;
+        }
+        if version < 6 {
+            config.coin_chance = 0.;
+        }
+        if version < 7 {
+            config.combo_window = 0.;
+            config.time_bonus = 0;
+        }
+        let mut difficulty = None;
+        let mut curve = DifficultyCurve::default();
+        let mut inputs = Vec::new();
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let bad = |err: &dyn std::fmt::Display| format!("bad {key}: {err}");
+            match key {
+                "difficulty" => difficulty = Some(parse_difficulty(value).ok_or(bad(&value))?),
+                "lives" => config.lives = value.parse().map_err(|err| bad(&err))?,
+                "check_point_time" => config.check_point_time = value.parse().map_err(|err| bad(&err))?,
+                "check_point_every" => config.check_point_every = value.parse().map_err(|err| bad(&err))?,
+                "lookahead" => config.lookahead = value.parse().map_err(|err| bad(&err))?,
+                "decoys" => config.decoys = value.parse().map_err(|err| bad(&err))?,
+                "branch_chance" => config.branch_chance = value.parse().map_err(|err| bad(&err))?,
+                "branch_depth" => config.branch_depth = value.parse().map_err(|err| bad(&err))?,
+                "hazards" => config.hazards = value.parse().map_err(|err| bad(&err))?,
+                "hazard_weights" => config.hazard_weights = parse_hazard_weights(value).ok_or(bad(&value))?,
+                "pickup_chance" => config.pickup_chance = value.parse().map_err(|err| bad(&err))?,
+                "coin_chance" => config.coin_chance = value.parse().map_err(|err| bad(&err))?,
+                "combo_window" => config.combo_window = value.parse().map_err(|err| bad(&err))?,
+                "time_bonus" => config.time_bonus = value.parse().map_err(|err| bad(&err))?,
+                "curve" => curve.0.push(parse_curve_row(value).ok_or(format!("bad curve row `{value}`"))?),
+                _ => inputs.push(parse_input(line)?),
+            }
+        }
+
+        curve.check().map_err(|err| format!("bad curve: {err}"))?;
+
+        // replays from before version 9 go by the first difficulty their options match,
+        // anything else was played on custom options
+        let difficulty = difficulty.unwrap_or_else(|| Difficulty::ALL.into_iter()
+            .find(|difficulty| difficulty.config(&config) == config)
+            .unwrap_or(Difficulty::Custom));
+
+        Ok(Replay { seed, difficulty, config, curve, inputs })
+    }
+}
+
+fn parse_difficulty(value: &str) -> Option<Difficulty> {
+    Difficulty::ALL.into_iter().find(|difficulty| difficulty.name() == value)
+}
+
+fn parse_curve_row(value: &str) -> Option<CurveRow> {
+    let mut fields = value.split_whitespace();
+    Some(CurveRow {
+        score: fields.next()?.parse().ok()?,
+        time_scale: fields.next()?.parse().ok()?,
+        extra_spacing: fields.next()?.parse().ok()?,
+        decoys: fields.next()?.parse().ok()?,
+        // rows from before hazards end here
+        hazards: fields.next().map_or(Some(false), |field| field.parse().ok())?,
+    })
+}
+
+fn parse_hazard_weights(value: &str) -> Option<HazardWeights> {
+    let mut fields = value.split_whitespace().map(|field| field.parse().ok());
+    Some(HazardWeights {
+        normal: fields.next()??,
+        crumbling: fields.next()??,
+        drifting: fields.next()??,
+        spiked: fields.next()??,
+    })
+}
+
+fn parse_input(line: &str) -> Result<ReplayInput, String> {
+    let mut fields = line.split_whitespace();
+    let direction = match fields.next() {
+        Some("L") => Direction::Left,
+        Some("R") => Direction::Right,
+        _ => return Err(format!("bad direction in `{line}`")),
+    };
+    let checkpoint = fields.next()
+        .and_then(|field| field.parse().ok())
+        .ok_or(format!("bad checkpoint in `{line}`"))?;
+    let elapsed = fields.next()
+        .and_then(|field| field.parse().ok())
+        .ok_or(format!("bad time in `{line}`"))?;
+    // inputs from before version 8 end here, and play back as they always did
+    let lives_lost = fields.next()
+        .map_or(Some(0), |field| field.parse().ok())
+        .ok_or(format!("bad lives lost in `{line}`"))?;
+    Ok(ReplayInput { direction, checkpoint, elapsed, lives_lost })
+}
+
+impl Replay {
+    pub fn record(&mut self, direction: Direction, climb: &Climb) {
+        self.inputs.push(ReplayInput {
+            direction,
+            checkpoint: climb.check_points_reached(),
+            elapsed: climb.clock().elapsed(),
+            lives_lost: lives_lost(climb),
+        });
+    }
+
+    // the last press was replaced before it was taken, it keeps its time
+    pub fn redirect(&mut self, direction: Direction) {
+        if let Some(input) = self.inputs.last_mut() {
+            input.direction = direction;
+        }
+    }
+}
+
+fn lives_lost(climb: &Climb) -> u32 {
+    climb.config().lives.saturating_sub(climb.lives())
+}
+
+// the run currently being played
+#[derive(Resource, Default)]
+pub struct Recorder(pub Replay);
+
+// the run replay mode plays back, the last finished run unless one was passed at launch
+#[derive(Resource, Default)]
+pub struct Playback {
+    pub replay: Option<Replay>,
+    next: usize,
+    from_launch: bool,
+}
+
+impl Playback {
+    pub fn load(storage: &Storage) -> Self {
+        if let Some(replay) = launch_replay() {
+            return Playback {
+                replay: Some(replay),
+                next: 0,
+                from_launch: true,
+            };
+        }
+        Playback {
+            replay: storage.read(LAST_REPLAY_NAME).and_then(|contents| parse(&contents, LAST_REPLAY_NAME)),
+            ..default()
+        }
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.replay.as_ref().map(|replay| replay.seed)
+    }
+}
+
+fn parse(contents: &str, source: &str) -> Option<Replay> {
+    match contents.parse() {
+        Ok(replay) => Some(replay),
+        Err(err) => {
+            warn!("ignoring replay {source}: {err}");
+            None
+        },
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn launch_replay() -> Option<Replay> {
+    let path = crate::args::get("replay")?;
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => parse(&contents, &path),
+        Err(err) => {
+            warn!("could not read replay {path}: {err}");
+            None
+        },
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn launch_replay() -> Option<Replay> {
+    None
+}
+
+pub fn load_playback(mut playback: ResMut<Playback>, storage: Res<Storage>) {
+    *playback = Playback::load(&storage);
+}
+
+// a replay passed at launch skips the menu
+pub fn start_launch_replay(mut mode: ResMut<GameMode>,
+    mut game_state: ResMut<NextState<crate::GameState>>,
+    playback: Res<Playback>,
+)
+{
+    if playback.from_launch {
+        *mode = GameMode::Replay;
+        game_state.set(crate::GameState::Playing);
+    }
+}
+
+pub fn start_recording(mut recorder: ResMut<Recorder>,
+    mut playback: ResMut<Playback>,
+    seed: Res<TowerSeed>,
+    game: Res<Game>,
+)
+{
+    recorder.0 = Replay {
+        seed: seed.seed(),
+        difficulty: game.difficulty,
+        config: game.climb.config().clone(),
+        curve: game.climb.curve().clone(),
+        inputs: Vec::new(),
+    };
+    playback.next = 0;
+}
+
+pub fn store_recording(recorder: Res<Recorder>,
+    mut playback: ResMut<Playback>,
+    mode: Res<GameMode>,
+    mut storage: ResMut<Storage>,
+)
+{
+    if *mode == GameMode::Replay {
+        return;
+    }
+    if let Err(err) = storage.write(LAST_REPLAY_NAME, &recorder.0.to_string()) {
+        warn!("could not write replay: {err}");
+    }
+    playback.replay = Some(recorder.0.clone());
+}
+
+// sends each recorded input once its checkpoint timer reaches the recorded time
+pub fn feed_playback(mut playback: ResMut<Playback>,
+    game: Res<Game>,
+    mut inputs: EventWriter<DirectionInput>,
+)
+{
+    // one press at a time
+    if game.player.buffered.is_some() {
+        return;
+    }
+    let Some(input) = playback.replay.as_ref().and_then(|replay| replay.inputs.get(playback.next)).copied() else {
+        return;
+    };
+    // the clock starts over at each hour glass and each lost life, so the press waits for both
+    let climb = &game.climb;
+    let now = (climb.check_points_reached(), lives_lost(climb));
+    let due = (input.checkpoint, input.lives_lost);
+    if now > due || (now == due && climb.clock().elapsed() >= input.elapsed)
+    {
+        inputs.send(DirectionInput(input.direction));
+        playback.next += 1;
+    }
+}
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use crate::assets::GameAssets;
+use crate::GameState;
 use crate::game::Game;
 
 #[derive(Component)]
@@ -13,17 +15,23 @@ pub struct ScoreEntity;
 #[derive(Component)]
 pub struct HighScoreEntity;
 
-impl Score {
-    pub fn to_string(&self) -> String {
-        self.text.clone() + &self.value.to_string()
-    }
+#[derive(Component)]
+pub struct LivesEntity;
 
-    pub fn increment(&mut self) {
-        self.value += 1;
+fn lives_text(game: &Game) -> String {
+    format!("Lives: {}", game.climb.lives())
+}
+
+impl std::fmt::Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", self.text, self.value)
     }
+}
 
-    pub fn init_high_score(&mut self) {
+impl Score {
+    pub fn init_high_score(&mut self, value: i32) {
         self.text = String::from("High Score: ");
+        self.value = value;
     }
 }
 
@@ -37,11 +45,11 @@ impl Default for Score {
 }
 
 pub fn load_scores(commands: &mut Commands,
-    asset_server: &mut Res<AssetServer>,
+    assets: &GameAssets,
     game: &mut ResMut<Game>,
 )
 {
-    commands.spawn(NodeBundle {
+    commands.spawn((NodeBundle {
         style: Style {
             width: Val::Percent(100.),
             height: Val::Percent(100.),
@@ -53,13 +61,13 @@ pub fn load_scores(commands: &mut Commands,
             ..default()
         },
         ..default()
-        }
+        }, StateScoped(GameState::Playing))
     )
     .with_children(|parent|{
         parent.spawn((TextBundle::from_section(
             game.high_score.to_string()
             , TextStyle { 
-                font: asset_server.load("FiraSans-Regular.ttf"),
+                font: assets.font.clone(),
                 font_size: 40.,
                 color: Color::WHITE,
             }
@@ -69,20 +77,38 @@ pub fn load_scores(commands: &mut Commands,
         parent.spawn((TextBundle::from_section(
             game.score.to_string()
             , TextStyle { 
-                font: asset_server.load("FiraSans-Regular.ttf"),
+                font: assets.font.clone(),
                 font_size: 40.,
                 color: Color::WHITE,
             }
         ), ScoreEntity));
+        // only runs with lives to spare show them
+        if game.climb.config().lives > 1 {
+            parent.spawn((TextBundle::from_section(
+                lives_text(game)
+                , TextStyle { 
+                    font: assets.font.clone(),
+                    font_size: 40.,
+                    color: Color::WHITE,
+                }
+            ), LivesEntity));
+        }
     });
 }
 
 
-pub fn update_score(mut score_query: Query<&mut Text, (With<ScoreEntity>, Without<HighScoreEntity>)>,
-    mut high_score_query: Query<&mut Text, (With<HighScoreEntity>, Without<ScoreEntity>)>,
+// one of the hud texts and not the other two
+type HudText<T, A, B> = (With<T>, Without<A>, Without<B>);
+
+pub fn update_score(mut score_query: Query<&mut Text, HudText<ScoreEntity, HighScoreEntity, LivesEntity>>,
+    mut high_score_query: Query<&mut Text, HudText<HighScoreEntity, ScoreEntity, LivesEntity>>,
+    mut lives_query: Query<&mut Text, HudText<LivesEntity, ScoreEntity, HighScoreEntity>>,
     game: Res<Game>, 
 ) 
 {
+    for mut lives in &mut lives_query {
+        lives.sections[0].value = lives_text(&game);
+    }
     for mut score in &mut score_query {
         score.sections[0].value =  game.score.to_string();
     }
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+// picks the seed each run's tower is generated from,
+// the same seed always gives the same correct_path
+#[derive(Resource)]
+pub struct TowerSeed {
+    seed: u64,
+    fixed: bool,
+}
+
+impl TowerSeed {
+    // a fixed seed is reused for every run, otherwise each run rolls a new one
+    pub fn new(seed: Option<u64>) -> Self {
+        TowerSeed {
+            seed: seed.unwrap_or_else(rand::random),
+            fixed: seed.is_some(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // a seed passed in here, like the daily one, wins over the launch seed
+    pub fn new_run(&mut self, seed: Option<u64>) {
+        if let Some(seed) = seed {
+            self.seed = seed;
+        }
+        else if !self.fixed {
+            self.seed = rand::random();
+        }
+    }
+}
@@ -1,70 +1,76 @@
 use bevy::prelude::*;
+use crate::assets::GameAssets;
 use crate::GameState;
-use crate::game::Game;
+use crate::game::{Game, GameMode};
+use crate::rules::ScoreBreakdown;
+use crate::save::SaveData;
+use crate::game::seed::TowerSeed;
+use crate::settings::SettingsScreen;
+use crate::ui::{self, BLUE, PINK, PURPLE};
 
-const BLUE: Color = Color::srgb(0.0,0.67,1.0);
-const PINK: Color = Color::srgb(1.0,0.67,1.0);
-const PURPLE: Color = Color::srgb(0.69, 0.67, 1.0);
-
-#[derive(Component)]
-struct GameOverEntity;
+#[derive(Component, Clone, Copy)]
+enum GameOverButton {
+    Menu,
+    Settings,
+}
 
 pub struct GameOverPlugin;
 
 impl Plugin for GameOverPlugin {
     fn build(&self, app: &mut App){
         app
-            .add_systems(OnEnter(GameState::GameOver), (load_game_over, set_high_score))
-            .add_systems(OnExit(GameState::GameOver),  (clear_game_over, reset_game, reset_camera))
-            .add_systems(Update, button_system.run_if(in_state(GameState::GameOver)));
+            .add_systems(OnEnter(GameState::GameOver), (record_score, load_game_over).chain())
+            .add_systems(Update, button_system
+                .run_if(in_state(SettingsScreen::Closed))
+                .run_if(in_state(GameState::GameOver)));
     }
 }
 
 fn load_game_over(mut commands: Commands,
-    entities: Query<Entity, (Without<Camera>, Without<Window>)>,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     game: Res<Game>,
+    seed: Res<TowerSeed>,
+    mode: Res<GameMode>,
+    save: Res<SaveData>,
 )
 {
-    clear_ui(&mut commands, entities);
-    game_over_ui(&mut commands, asset_server, game);
+    let todays_best = match *mode {
+        GameMode::Classic | GameMode::Replay => None,
+        GameMode::Daily(date) => Some(save.daily_best(&date.to_string())),
+    };
+    let coins = (*mode != GameMode::Replay).then(|| (game.climb.coins(), save.coins));
+    game_over_ui(&mut commands, assets, game, seed, todays_best, coins);
 }
 
-fn clear_game_over(mut commands: Commands,
-    entities: Query<Entity, (Without<Camera>, Without<Window>)>,
+// replays don't bank coins, they were had the first time round
+// runs left from the pause menu come through here too, see pause::end_run
+pub(crate) fn record_score(mut game: ResMut<Game>,
+    mode: Res<GameMode>,
+    mut save: ResMut<SaveData>,
 )
 {
-    clear_ui(&mut commands, entities);
-}
-
-fn clear_ui(commands: &mut Commands,
-    entities: Query<Entity, (Without<Camera>, Without<Window>)>,
-)
-{
-    for entity in &entities {
-        commands.entity(entity).despawn();
+    if *mode != GameMode::Replay {
+        save.coins += game.climb.coins();
     }
-}
-
-fn reset_game(mut game: ResMut<Game>) {
-    game.reset();
-}
-
-fn reset_camera(game: ResMut<Game>,
-    mut transforms: Query<&mut Transform>
-)
-{
-    *transforms.get_mut(game.camera.unwrap()).unwrap() = Transform::default();
-}
-
-fn set_high_score(mut game: ResMut<Game>) {
-    if game.score.value > game.high_score.value
-    {
-        game.high_score.value = game.score.value;
+    match *mode {
+        GameMode::Classic => {
+            if game.set_high_score() {
+                save.record_high_score(game.difficulty, game.high_score.value);
+            }
+        },
+        GameMode::Daily(date) => save.record_daily(&date.to_string(), game.score.value),
+        GameMode::Replay => {},
     }
 }
 
-fn game_over_ui(commands: &mut Commands, asset_server: Res<AssetServer>, game: Res<Game>) {
+fn game_over_ui(commands: &mut Commands,
+    assets: Res<GameAssets>,
+    game: Res<Game>,
+    seed: Res<TowerSeed>,
+    todays_best: Option<i32>,
+    // picked up this run and the balance with them banked
+    coins: Option<(u32, u32)>,
+) {
 
     commands.spawn((NodeBundle { 
         style: Style {
@@ -78,55 +84,96 @@ fn game_over_ui(commands: &mut Commands, asset_server: Res<AssetServer>, game: R
         },
         background_color: BackgroundColor(PURPLE),
         ..default()
-    }, GameOverEntity))
+    }, StateScoped(GameState::GameOver)))
     .with_children(|parent|{
-        parent.spawn((ButtonBundle {
-            style: Style {
-                width: Val::Px(300.0),
-                height: Val::Px(65.0),
-                border: UiRect::all(Val::Px(5.0)),
-                align_content: AlignContent::Center,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            background_color: BackgroundColor(PINK),
-            border_color: BorderColor(Color::BLACK),
-            border_radius: BorderRadius::MAX,
-            ..default()
-        }, GameOverEntity))
-        .with_children(|parent|{
-            parent.spawn((TextBundle::from_section(
-                "GAME OVER"
+        ui::spawn_button(parent, &assets, "GAME OVER", GameOverButton::Menu, 300., PINK);
+    })
+    .with_children(|parent|{
+        parent.spawn(TextBundle::from_section(
+            game.score.to_string()
+            , TextStyle { 
+                font: assets.font.clone(),
+                font_size: 40.,
+                color: Color::WHITE,
+            }
+        ));
+    })
+    .with_children(|parent|{
+        for line in breakdown_lines(game.climb.score_breakdown()) {
+            parent.spawn(TextBundle::from_section(
+                line
+                , TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 25.,
+                    color: Color::WHITE,
+                }
+            ));
+        }
+    })
+    .with_children(|parent|{
+        if let Some(best) = todays_best {
+            parent.spawn(TextBundle::from_section(
+                format!("Today's Best: {}", best)
                 , TextStyle { 
-                    font: asset_server.load("FiraSans-Regular.ttf"),
+                    font: assets.font.clone(),
                     font_size: 40.,
                     color: Color::WHITE,
                 }
-            ), GameOverEntity));
-        });
+            ));
+        }
     })
     .with_children(|parent|{
-        parent.spawn((TextBundle::from_section(
-            game.score.to_string()
+        if let Some((picked_up, balance)) = coins {
+            parent.spawn(TextBundle::from_section(
+                format!("Coins: +{} ({})", picked_up, balance)
+                , TextStyle { 
+                    font: assets.font.clone(),
+                    font_size: 25.,
+                    color: Color::WHITE,
+                }
+            ));
+        }
+    })
+    .with_children(|parent|{
+        parent.spawn(TextBundle::from_section(
+            format!("Seed: {}", seed.seed())
             , TextStyle { 
-                font: asset_server.load("FiraSans-Regular.ttf"),
-                font_size: 40.,
+                font: assets.font.clone(),
+                font_size: 25.,
                 color: Color::WHITE,
             }
-        ), GameOverEntity));
+        ));
+    })
+    .with_children(|parent|{
+        ui::spawn_button(parent, &assets, "SETTINGS", GameOverButton::Settings, 300., PINK);
     });
 }
 
+// where the score came from, bonuses the run never earned are left off
+pub fn breakdown_lines(breakdown: &ScoreBreakdown) -> Vec<String> {
+    let mut lines = vec![format!("Climbing: {}", breakdown.climbing)];
+    if breakdown.combo > 0 {
+        lines.push(format!("Combos: +{}", breakdown.combo));
+    }
+    if breakdown.time > 0 {
+        lines.push(format!("Time Bonus: +{}", breakdown.time));
+    }
+    lines
+}
+
 
-fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor)>
-    , mut game_state: ResMut<NextState<GameState>>) 
+fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &GameOverButton)>
+    , mut game_state: ResMut<NextState<GameState>>
+    , mut settings_screen: ResMut<NextState<SettingsScreen>>) 
 {
-    for (interaction, mut background_color) in &mut interaction_query {
+    for (interaction, mut background_color, button) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *background_color = BackgroundColor(BLUE);
-                game_state.set(GameState::Menu);
+                match button {
+                    GameOverButton::Menu => game_state.set(GameState::Menu),
+                    GameOverButton::Settings => settings_screen.set(SettingsScreen::Open),
+                }
             },
             Interaction::Hovered => *background_color = BackgroundColor(BLUE),
             Interaction::None => *background_color = BackgroundColor(PINK),
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+use bevy::input::InputPlugin;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::ExitCondition;
+use std::time::Duration;
+use crate::{menu, game, game_over, save, settings, shop, audio, assets, GameState};
+use crate::game::{Direction, Game, GameMode, PlayerAction};
+use crate::game::input::DirectionInput;
+use crate::game::seed::TowerSeed;
+use crate::rules::{ClimbConfig, Difficulty};
+use crate::settings::Settings;
+
+const FRAME_TIME: Duration = Duration::from_micros(16_667);
+const MAX_SETTLE_FRAMES: usize = 600;
+
+// the whole game on MinimalPlugins, no window, rendering or audio,
+// assets are handed out as handles but never actually load
+// and time moves one fixed frame per update
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .add_plugins((
+                MinimalPlugins,
+                StatesPlugin,
+                InputPlugin,
+                AssetPlugin::default(),
+                // window events but no window
+                WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                },
+            ))
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_asset::<AudioSource>()
+            .init_asset::<Font>()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME))
+            // nothing actually loads headless, so skip the loading screen
+            .insert_state(GameState::Menu)
+            .enable_state_scoped_entities::<GameState>()
+            .add_plugins((
+                save::SavePlugin { in_memory: true },
+                assets::AssetsPlugin,
+                settings::SettingsPlugin,
+                shop::ShopPlugin,
+                audio::SoundPlugin,
+                menu::MenuPlugin,
+                game::GamePlugin,
+                game_over::GameOverPlugin,
+            ))
+            .add_systems(Startup, crate::spawn_camera);
+    }
+}
+
+// drives a headless app for integration tests
+pub struct Sim {
+    pub app: App,
+}
+
+impl Sim {
+    // sits on the menu with every run using the given seed
+    pub fn new(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins(HeadlessPlugin)
+            .insert_resource(TowerSeed::new(Some(seed)));
+        let mut sim = Sim { app };
+        sim.step();
+        sim
+    }
+
+    pub fn start(&mut self, mode: GameMode) {
+        *self.app.world_mut().resource_mut::<GameMode>() = mode;
+        self.set_state(GameState::Playing);
+    }
+
+    // a classic run on the Custom difficulty, so it plays with exactly these options
+    pub fn start_custom(&mut self, config: ClimbConfig) {
+        let mut settings = self.resource_mut::<Settings>();
+        settings.difficulty = Difficulty::Custom;
+        settings.climb = config;
+        self.start(GameMode::Classic);
+    }
+
+    pub fn set_state(&mut self, state: GameState) {
+        self.app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+        self.step();
+    }
+
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn run_for(&mut self, secs: f32) {
+        let frames = (secs / FRAME_TIME.as_secs_f32()).ceil() as usize;
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    // presses a direction and steps until the player is resting or falling again
+    pub fn press(&mut self, direction: Direction) {
+        self.app.world_mut().send_event(DirectionInput(direction));
+        self.step();
+        self.settle();
+    }
+
+    pub fn press_correct(&mut self) {
+        let direction = self.next_direction();
+        self.press(direction);
+    }
+
+    pub fn press_wrong(&mut self) {
+        let direction = self.next_direction().opposite();
+        self.press(direction);
+    }
+
+    // presses and releases a key over two frames
+    pub fn tap_key(&mut self, key_code: KeyCode) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            self.app.world_mut().send_event(KeyboardInput {
+                key_code,
+                logical_key: Key::Unidentified(NativeKey::Unidentified),
+                state,
+                window: Entity::PLACEHOLDER,
+            });
+            self.step();
+        }
+    }
+
+    // there's no ui picking headless, so mark the matching button as pressed directly
+    pub fn click<C: Component + PartialEq>(&mut self, button: C) {
+        let world = self.app.world_mut();
+        let entity = world
+            .query::<(Entity, &C)>()
+            .iter(world)
+            .find(|(_, component)| **component == button)
+            .map(|(entity, _)| entity)
+            .expect("no such button on screen");
+        world.entity_mut(entity).insert(Interaction::Pressed);
+        self.step();
+        if let Some(mut entity) = self.app.world_mut().get_entity_mut(entity) {
+            entity.insert(Interaction::None);
+        }
+        self.step();
+    }
+
+    // steps until no player action or game state change is pending
+    pub fn settle(&mut self) {
+        for _ in 0..MAX_SETTLE_FRAMES {
+            let pending = matches!(self.app.world().resource::<NextState<PlayerAction>>(), NextState::Pending(_))
+                || matches!(self.app.world().resource::<NextState<GameState>>(), NextState::Pending(_));
+            if !pending && self.player_action() != PlayerAction::Jump {
+                return;
+            }
+            self.step();
+        }
+        panic!("game did not settle within {MAX_SETTLE_FRAMES} frames");
+    }
+
+    // steps until the fall finishes and the game over screen is up
+    pub fn finish_fall(&mut self) {
+        for _ in 0..MAX_SETTLE_FRAMES {
+            if self.game_state() == GameState::GameOver {
+                return;
+            }
+            self.step();
+        }
+        panic!("player did not land within {MAX_SETTLE_FRAMES} frames");
+    }
+
+    pub fn next_direction(&self) -> Direction {
+        self.game().climb.next_direction().unwrap()
+    }
+
+    pub fn game(&self) -> &Game {
+        self.app.world().resource::<Game>()
+    }
+
+    pub fn score(&self) -> i32 {
+        self.game().score.value
+    }
+
+    pub fn game_state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    pub fn player_action(&self) -> PlayerAction {
+        *self.app.world().resource::<State<PlayerAction>>().get()
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.app.world_mut().resource_mut::<R>()
+    }
+}
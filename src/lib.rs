@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+
+pub mod menu;
+pub mod game;
+pub mod game_over;
+pub mod save;
+pub mod settings;
+pub mod shop;
+pub mod audio;
+pub mod assets;
+pub mod args;
+pub mod headless;
+pub mod rules;
+pub mod ui;
+
+pub const WINDOW_Y: f32 = 600.;
+pub const WINDOW_X: f32 = 800.;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+pub enum GameState {
+    #[default]
+    Loading,
+    Menu,
+    Playing,
+    GameOver,
+}
+
+pub fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
@@ -1,19 +1,6 @@
 use bevy::prelude::*;
 use bevy::asset::AssetMetaCheck;
-mod menu;
-mod game;
-mod game_over;
-
-const WINDOW_Y: f32 = 600.;
-const WINDOW_X: f32 = 800.;
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
-enum GameState {
-    #[default]
-    Menu,
-    Playing,
-    GameOver,
-}
+use uppy_up::{menu, game, game_over, save, settings, shop, audio, assets, GameState, WINDOW_X, WINDOW_Y};
 
 
 fn main() {
@@ -34,15 +21,19 @@ fn main() {
             }),
         )
         .init_state::<GameState>()
+        .enable_state_scoped_entities::<GameState>()
         .add_plugins((
+            save::SavePlugin::default(),
+            assets::AssetsPlugin,
+            settings::SettingsPlugin,
+            shop::ShopPlugin,
+            audio::SoundPlugin,
             menu::MenuPlugin,
             game::GamePlugin,
             game_over::GameOverPlugin,
+            // not part of GamePlugin, see CurveFilePlugin
+            game::curve::CurveFilePlugin,
         ))
-        .add_systems(Startup, start)
+        .add_systems(Startup, uppy_up::spawn_camera)
         .run();
 }
-
-fn start(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default());
-}
@@ -1,37 +1,56 @@
 use bevy::prelude::*;
-use crate::game::Game;
+use crate::assets::GameAssets;
+use crate::game::{Game, GameMode, PlayerAction};
+use crate::game::daily::UtcDate;
+use crate::game::replay::Playback;
+use crate::settings::{Settings, SettingsScreen};
+use crate::shop::ShopScreen;
+use crate::ui::{self, BLUE, PURPLE};
 
 use super::GameState;
 
 pub struct MenuPlugin;
 
-#[derive(Component)]
-struct MenuEntity;
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum MenuButton {
+    Play,
+    Daily,
+    Replay,
+    Settings,
+    // cycles through the difficulties
+    Difficulty,
+    Shop,
+}
 
-const BLUE: Color = Color::srgb(0.0,0.67,1.0);
-//const PINK: Color = Color::srgb(1.0,0.67,1.0);
-const PURPLE: Color = Color::srgb(0.69, 0.67, 1.0);
-const INSTRUCTIONS: &str = 
-"Use (←, →) or (a, d) to go up. \
-Move in the wrong direction or run out of time and it's game over. \
-Reach an hour glass to earn more time.";
+#[derive(Component)]
+struct InstructionsText;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App){
         app
+            .add_systems(Startup, load_background)
             .add_systems(OnEnter(GameState::Menu), 
     (
                 load_button,
                 load_logo,
-                load_background,
                 load_instructions,
+                reset_run,
             ))
-            .add_systems(OnExit(GameState::Menu), clear_menu)
-            .add_systems(Update, button_system.run_if(in_state(GameState::Menu)));
+            .add_systems(Update, (
+                button_system
+                    .run_if(in_state(SettingsScreen::Closed))
+                    .run_if(in_state(ShopScreen::Closed)),
+                (update_instructions, update_difficulty).run_if(resource_changed::<Settings>),
+            )
+                .run_if(in_state(GameState::Menu)));
     }
 }
 
-fn load_button(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn load_button(mut commands: Commands,
+    assets: Res<GameAssets>,
+    playback: Res<Playback>,
+    settings: Res<Settings>,
+) {
 
     commands.spawn((NodeBundle { 
         style: Style {
@@ -41,60 +60,68 @@ fn load_button(mut commands: Commands, asset_server: Res<AssetServer>) {
             align_content: AlignContent::Center,
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
-
+            row_gap: Val::Px(20.),
             ..default()
         },
         ..default()
-    }, MenuEntity))
+    }, StateScoped(GameState::Menu)))
     .with_children(|parent|{
-        parent.spawn((ButtonBundle {
+        parent.spawn(NodeBundle {
             style: Style {
-                width: Val::Px(150.0),
-                height: Val::Px(65.0),
-                border: UiRect::all(Val::Px(5.0)),
-                align_content: AlignContent::Center,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(20.),
                 ..default()
             },
-            background_color: BackgroundColor(PURPLE),
-            border_color: BorderColor(Color::BLACK),
-            border_radius: BorderRadius::MAX,
             ..default()
-        }, MenuEntity))
+        })
         .with_children(|parent|{
-            parent.spawn((TextBundle::from_section(
-                "PLAY"
-                , TextStyle { 
-                    font: asset_server.load("FiraSans-Regular.ttf"),
-                    font_size: 40.,
-                    color: Color::WHITE,
-                }
-            ), MenuEntity));
+            ui::spawn_button(parent, &assets, "PLAY", MenuButton::Play, 150., PURPLE);
+            ui::spawn_button(parent, &assets, "DAILY", MenuButton::Daily, 150., PURPLE);
+            if playback.replay.is_some() {
+                ui::spawn_button(parent, &assets, "REPLAY", MenuButton::Replay, 150., PURPLE);
+            }
+            ui::spawn_button(parent, &assets, "SETTINGS", MenuButton::Settings, 150., PURPLE);
+        });
+        parent.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent|{
+            ui::spawn_button(parent, &assets, &difficulty_label(&settings), MenuButton::Difficulty, 300., PURPLE);
+            ui::spawn_button(parent, &assets, "SHOP", MenuButton::Shop, 150., PURPLE);
         });
     });
 }
 
-fn load_logo(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn difficulty_label(settings: &Settings) -> String {
+    settings.difficulty.name().to_uppercase()
+}
+
+fn load_logo(mut commands: Commands, assets: Res<GameAssets>) {
     commands.spawn((SpriteBundle {
-        texture: asset_server.load("logo.png"),
+        texture: assets.logo.clone(),
         transform: Transform::from_xyz(0., 180.0, 5.),
         ..default()
-    }, MenuEntity));
+    }, StateScoped(GameState::Menu)));
 }
 
+// the background stays for the whole session, the game scrolls it along with the camera
 fn load_background(mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
     mut game: ResMut<Game>,
 ) {
     game.background = Some(commands.spawn(SpriteBundle {
-        texture: asset_server.load("background_small.png"),
+        texture: assets.background.clone(),
         transform: Transform::from_xyz(0.0, 0.0, 0.0),
         ..default()
     }).id());
 }
 
-fn load_instructions(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn load_instructions(mut commands: Commands, assets: Res<GameAssets>, settings: Res<Settings>) {
 
     commands.spawn((NodeBundle { 
         style: Style {
@@ -111,33 +138,90 @@ fn load_instructions(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         ..default()
-    }, MenuEntity))
+    }, StateScoped(GameState::Menu)))
     .with_children(|parent|{
         parent.spawn((TextBundle::from_section(
-            INSTRUCTIONS,
+            settings.bindings.instructions(),
             TextStyle {
-                font: asset_server.load("FiraSans-Regular.ttf"),
+                font: assets.font.clone(),
                 font_size: 25.,
                 color: Color::WHITE,
             })
-        , MenuEntity));
+        , InstructionsText));
     });
 }
 
 
-fn clear_menu(mut commands: Commands, entity_query: Query<Entity, With<MenuEntity>>) {
-    for entity in entity_query.iter() {
-        commands.entity(entity).despawn();
+// back at the menu nothing from the last run is left, however it ended
+fn reset_run(mut game: ResMut<Game>,
+    mut transforms: Query<&mut Transform>,
+    mut player_action: ResMut<NextState<PlayerAction>>,
+)
+{
+    game.reset();
+    for entity in [game.camera, game.background].into_iter().flatten() {
+        if let Ok(mut transform) = transforms.get_mut(entity) {
+            *transform = Transform::default();
+        }
+    }
+    player_action.set(PlayerAction::Rest);
+}
+
+fn update_instructions(mut query: Query<&mut Text, With<InstructionsText>>,
+    settings: Res<Settings>,
+)
+{
+    for mut text in &mut query {
+        text.sections[0].value = settings.bindings.instructions();
+    }
+}
+
+fn update_difficulty(buttons: Query<(&MenuButton, &Children)>,
+    mut texts: Query<&mut Text>,
+    settings: Res<Settings>,
+)
+{
+    for (button, children) in &buttons {
+        if *button != MenuButton::Difficulty {
+            continue;
+        }
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = difficulty_label(&settings);
+            }
+        }
     }
 }
 
-fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor)>
-    , mut game_state: ResMut<NextState<GameState>>) 
+fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &MenuButton), Changed<Interaction>>
+    , mut game_state: ResMut<NextState<GameState>>
+    , mut settings_screen: ResMut<NextState<SettingsScreen>>
+    , mut shop_screen: ResMut<NextState<ShopScreen>>
+    , mut settings: ResMut<Settings>
+    , mut mode: ResMut<GameMode>) 
 {
-    for (interaction, mut background_color) in &mut interaction_query {
+    for (interaction, mut background_color, button) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 *background_color = BackgroundColor(BLUE);
+                let next_mode = match button {
+                    MenuButton::Play => GameMode::Classic,
+                    MenuButton::Daily => GameMode::Daily(UtcDate::today()),
+                    MenuButton::Replay => GameMode::Replay,
+                    MenuButton::Settings => {
+                        settings_screen.set(SettingsScreen::Open);
+                        continue;
+                    },
+                    MenuButton::Difficulty => {
+                        settings.difficulty = settings.difficulty.next();
+                        continue;
+                    },
+                    MenuButton::Shop => {
+                        shop_screen.set(ShopScreen::Open);
+                        continue;
+                    },
+                };
+                *mode = next_mode;
                 game_state.set(GameState::Playing);
             },
             Interaction::Hovered => *background_color = BackgroundColor(BLUE),
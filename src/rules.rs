@@ -0,0 +1,604 @@
+// the climbing rules on their own, no bevy in here
+// the game module draws whatever state a Climb is in
+
+mod tower;
+mod clock;
+mod curve;
+mod hazard;
+mod power_up;
+mod scoring;
+pub use tower::{Detour, PlatformKind, Rung, Tower};
+pub use clock::{CheckPointClock, CHECK_POINT_TIME};
+pub use curve::{CurveRow, DifficultyCurve};
+pub use hazard::{Drift, HazardWeights, CRUMBLE_DELAY, DRIFT_PERIOD, SPIKE_TIME};
+pub use power_up::{Effects, PowerUp, DOUBLER_TIME, FREEZE_TIME, REVEAL_TIME};
+pub use scoring::{Combo, ScoreBreakdown, COMBO_STEP, COMBO_WINDOW, MAX_MULTIPLIER, TIME_BONUS};
+use serde::{Deserialize, Serialize};
+
+pub const START_X: f32 = 0.0;
+pub const START_Y: f32 = -200.;
+pub const X_INC: f32 = 70.;
+pub const Y_INC: f32 = 40.;
+// how many platforms are generated ahead of the player
+pub const LOOKAHEAD: usize = 30;
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Location {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for Location {
+    fn default() -> Self {
+        Location {
+            x: START_X,
+            y: START_Y,
+        }
+    }
+}
+
+impl Location {
+    // the platform one step up in the given direction
+    pub fn step(&self, dir: Direction) -> Location {
+        let x = match dir {
+            Direction::Left => self.x - X_INC,
+            Direction::Right => self.x + X_INC,
+        };
+        Location {
+            x,
+            y: self.y + Y_INC,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Direction {
+    #[default]
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Step {
+    // landed on the next platform, the tower grew to new_platform,
+    // more than one step when reaching an hour glass spaces the next one further out,
+    // points are what the platforms scored and time_bonus what the clock left at an hour glass
+    Climbed {
+        check_point: bool,
+        new_platform: Location,
+        kind: PlatformKind,
+        pickup: Option<PowerUp>,
+        points: i32,
+        time_bonus: i32,
+    },
+    // stepped onto a decoy branch, nothing scored, dead_end is its last cloud
+    // which crumbles under the player, see Climb::crumble
+    Branched {
+        location: Location,
+        dead_end: bool,
+    },
+    // stepped round the next platform onto its detour, nothing scored yet,
+    // coin is false if it was taken on an earlier life
+    Detoured {
+        location: Location,
+        coin: bool,
+    },
+    WrongDirection,
+    // a wrong step a shield took, the player stays put
+    Shielded,
+}
+
+// the options a run is played with, saved in the settings and in replays
+// the defaults are the normal difficulty
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct ClimbConfig {
+    // one life is the classic game, a mistake ends the run
+    pub lives: u32,
+    // seconds to reach each hour glass
+    pub check_point_time: f32,
+    // platforms from one hour glass to the next, at most the lookahead
+    // before the difficulty curve spaces them out
+    pub check_point_every: usize,
+    // how many platforms are generated, and shown, ahead of the player
+    pub lookahead: usize,
+    // decoy branches off the wrong side of some steps
+    pub decoys: bool,
+    // the chance each step gets a branch when decoys are on
+    pub branch_chance: f32,
+    // the most clouds in a branch, fewer against the walls
+    pub branch_depth: usize,
+    // crumbling, drifting and spiked platforms mixed in
+    pub hazards: bool,
+    pub hazard_weights: HazardWeights,
+    // the chance each platform has a power up on it, none before the first hour glass
+    pub pickup_chance: f32,
+    // the chance each zigzag has a coin on a cloud round it, none before the first hour glass
+    pub coin_chance: f32,
+    // seconds between jumps that keep a combo going, 0 for no combos
+    pub combo_window: f32,
+    // points a second left on the clock at each hour glass
+    pub time_bonus: i32,
+}
+
+impl Default for ClimbConfig {
+    fn default() -> Self {
+        ClimbConfig {
+            lives: 1,
+            check_point_time: CHECK_POINT_TIME,
+            check_point_every: LOOKAHEAD,
+            lookahead: LOOKAHEAD,
+            decoys: false,
+            branch_chance: 0.3,
+            branch_depth: 2,
+            hazards: false,
+            hazard_weights: HazardWeights::default(),
+            pickup_chance: 0.08,
+            coin_chance: 0.15,
+            combo_window: COMBO_WINDOW,
+            time_bonus: TIME_BONUS,
+        }
+    }
+}
+
+// the options custom runs can step up and down
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClimbOption {
+    CheckPointTime,
+    CheckPointEvery,
+    Lookahead,
+    BranchChance,
+    BranchDepth,
+}
+
+impl ClimbConfig {
+    // seconds and branch clouds go one at a time, platform counts and branch chance in fives,
+    // all kept playable
+    pub fn step(&mut self, option: ClimbOption, step: i32) {
+        let stepped = |value: usize, by: i32, min: usize, max: usize| {
+            (value as i32 + by).clamp(min as i32, max as i32) as usize
+        };
+        match option {
+            ClimbOption::CheckPointTime => {
+                self.check_point_time = (self.check_point_time.round() + step as f32).clamp(5., 60.);
+            },
+            ClimbOption::CheckPointEvery => {
+                self.check_point_every = stepped(self.check_point_every, step * 5, 5, self.lookahead);
+            },
+            ClimbOption::Lookahead => {
+                self.lookahead = stepped(self.lookahead, step * 5, 10, 60);
+            },
+            ClimbOption::BranchChance => {
+                let percent = (self.branch_chance * 100.).round() as i32 + step * 5;
+                self.branch_chance = percent.clamp(5, 60) as f32 / 100.;
+            },
+            ClimbOption::BranchDepth => {
+                self.branch_depth = stepped(self.branch_depth, step, 1, 5);
+            },
+        }
+        self.check_point_every = self.check_point_every.min(self.lookahead);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    // whatever the player set up in the settings
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Custom => "Custom",
+        }
+    }
+
+    pub fn next(self) -> Difficulty {
+        let index = Difficulty::ALL.iter().position(|difficulty| *difficulty == self).unwrap();
+        Difficulty::ALL[(index + 1) % Difficulty::ALL.len()]
+    }
+
+    // the presets keep the player's lives, custom takes everything from the settings
+    pub fn config(self, custom: &ClimbConfig) -> ClimbConfig {
+        let preset = match self {
+            Difficulty::Easy => ClimbConfig {
+                check_point_time: 25.,
+                check_point_every: 20,
+                lookahead: 40,
+                pickup_chance: 0.12,
+                ..ClimbConfig::default()
+            },
+            Difficulty::Normal => ClimbConfig::default(),
+            Difficulty::Hard => ClimbConfig {
+                check_point_time: 12.,
+                check_point_every: 25,
+                lookahead: 25,
+                decoys: true,
+                branch_depth: 3,
+                hazards: true,
+                pickup_chance: 0.04,
+                ..ClimbConfig::default()
+            },
+            Difficulty::Custom => return custom.clone(),
+        };
+        ClimbConfig {
+            lives: custom.lives,
+            ..preset
+        }
+    }
+}
+
+// one run up one tower
+pub struct Climb {
+    config: ClimbConfig,
+    curve: DifficultyCurve,
+    tower: Tower,
+    player: Location,
+    score: ScoreBreakdown,
+    combo: Combo,
+    clock: CheckPointClock,
+    check_point: Location,
+    check_points_reached: u32,
+    lives: u32,
+    // the last hour glass reached, where a lost life starts again from
+    respawn: Location,
+    respawn_score: ScoreBreakdown,
+    // the platforms climbed since then, handed back to the tower on a lost life
+    climbed: Vec<Rung>,
+    // how far along the next rung's branch the player is
+    on_branch: Option<usize>,
+    // seconds of play, drifting platforms move with it
+    time: f32,
+    // seconds until the crumbling platform the player is on gives way
+    crumbling: Option<f32>,
+    effects: Effects,
+    // standing on the next rung's detour
+    on_detour: bool,
+    coins: u32,
+}
+
+impl Default for Climb {
+    fn default() -> Self {
+        Climb::new(0)
+    }
+}
+
+impl Climb {
+    pub fn new(seed: u64) -> Self {
+        Climb::with_config(seed, ClimbConfig::default())
+    }
+
+    pub fn with_config(seed: u64, config: ClimbConfig) -> Self {
+        Climb::with_curve(seed, config, DifficultyCurve::default())
+    }
+
+    pub fn with_curve(seed: u64, config: ClimbConfig, curve: DifficultyCurve) -> Self {
+        let mut tower = Tower::new(seed);
+        set_generation(&mut tower, &config, &curve.at(0), false);
+        for _i in 0..config.lookahead.max(1) {
+            tower.grow();
+        }
+        let mut climb = Climb {
+            lives: config.lives.max(1),
+            clock: CheckPointClock::new(config.check_point_time),
+            config,
+            curve,
+            tower,
+            player: Location::default(),
+            score: ScoreBreakdown::default(),
+            combo: Combo::default(),
+            check_point: Location::default(),
+            check_points_reached: 0,
+            respawn: Location::default(),
+            respawn_score: ScoreBreakdown::default(),
+            climbed: Vec::new(),
+            on_branch: None,
+            time: 0.,
+            crumbling: None,
+            effects: Effects::default(),
+            on_detour: false,
+            coins: 0,
+        };
+        climb.next_check_point();
+        climb
+    }
+
+    // sets the clock, decoys, hazards and next hour glass from the curve at the current score,
+    // growing the tower if the hour glass is further out than the lookahead
+    fn next_check_point(&mut self) {
+        let row = self.curve.at(self.score.total());
+        self.clock = CheckPointClock::new(self.config.check_point_time * row.time_scale);
+        set_generation(&mut self.tower, &self.config, &row, self.check_points_reached > 0);
+        let every = self.config.check_point_every.clamp(1, self.config.lookahead.max(1)) + row.extra_spacing;
+        while self.tower.platforms().len() < every {
+            self.tower.grow();
+        }
+        self.tower.keep_plain(every - 1);
+        self.check_point = self.tower.platforms()[every - 1].clone();
+    }
+
+    // the way to the next platform from where the player stands, drifting platforms change it,
+    // on a branch it's the side the path is on
+    pub fn next_direction(&self) -> Option<Direction> {
+        if self.on_branch.is_some() || self.on_detour {
+            return self.tower.next_direction();
+        }
+        let next = self.next_platform()?;
+        Some(if next.x < self.player.x {Direction::Left} else {Direction::Right})
+    }
+
+    // where the next platform is right now
+    fn next_platform(&self) -> Option<Location> {
+        match self.tower.drift(0) {
+            Some(drift) => Some(drift.landing(self.time)),
+            None => self.tower.platforms().first().cloned(),
+        }
+    }
+
+    pub fn step(&mut self, dir: Direction) -> Step {
+        match self.try_step(dir) {
+            Step::WrongDirection if self.effects.use_shield() => {
+                self.combo.reset();
+                Step::Shielded
+            },
+            step => step,
+        }
+    }
+
+    fn try_step(&mut self, dir: Direction) -> Step {
+        // branches are never beside a drifting platform, so they leave the tower's way
+        let Some(correct) = self.tower.next_direction() else {
+            return Step::WrongDirection;
+        };
+        // from a detour the path's next step reaches the platform after the one gone round
+        if self.on_detour {
+            if dir != correct {
+                return Step::WrongDirection;
+            }
+            self.on_detour = false;
+            return self.climb(true);
+        }
+        // a branch only goes one way, straight on from where it left the path
+        let next_on_branch = match self.on_branch {
+            Some(index) if dir == correct.opposite() => Some(index + 1),
+            Some(_) => None,
+            None if Some(dir) == self.next_direction() => return self.climb(false),
+            None if self.tower.detours()[0].is_some() => return self.detour(),
+            None => Some(0),
+        };
+        let branch = &self.tower.branches()[0];
+        match next_on_branch.and_then(|index| Some((index, branch.get(index)?.clone()))) {
+            Some((index, location)) => {
+                self.on_branch = Some(index);
+                self.combo.reset();
+                self.crumbling = None;
+                self.player = location.clone();
+                Step::Branched { location, dead_end: index + 1 == branch.len() }
+            },
+            None => Step::WrongDirection,
+        }
+    }
+
+    // gone_round scores the platform a detour went round too
+    fn climb(&mut self, gone_round: bool) -> Step {
+        self.combo.jump(self.time, self.config.combo_window);
+        let mut points = if gone_round {self.pass()} else {0};
+        let landed = self.next_platform().unwrap();
+        let mut rung = self.tower.pop().unwrap();
+        let check_point = rung.platform == self.check_point;
+        let kind = rung.kind;
+        let pickup = rung.pickup.take();
+        self.climbed.push(rung);
+
+        self.player = landed;
+        points += self.score_platform();
+        self.tower.grow();
+        if let Some(power_up) = pickup {
+            self.effects.add(power_up);
+        }
+
+        self.crumbling = (kind == PlatformKind::Crumbling).then_some(CRUMBLE_DELAY);
+        if kind == PlatformKind::Spiked {
+            self.clock.tick(SPIKE_TIME);
+        }
+        let mut time_bonus = 0;
+        if check_point {
+            // the clock is reset for the next hour glass, so what's left is scored first
+            time_bonus = self.clock.remaining().floor().max(0.) as i32 * self.config.time_bonus;
+            self.score.time += time_bonus;
+            self.check_points_reached += 1;
+            self.next_check_point();
+            self.respawn = self.player.clone();
+            self.respawn_score = self.score;
+            self.climbed.clear();
+        }
+        Step::Climbed { check_point, new_platform: self.tower.top().clone(), kind, pickup, points, time_bonus }
+    }
+
+    // a point a platform, doubled by the doubler, then multiplied by the combo
+    fn score_platform(&mut self) -> i32 {
+        let points = if self.effects.active(PowerUp::Doubler) {2} else {1};
+        let multiplier = self.combo.multiplier();
+        self.score.climbing += points;
+        self.score.combo += points * (multiplier - 1);
+        points * multiplier
+    }
+
+    // onto the next rung's detour, its coin is collected on landing
+    fn detour(&mut self) -> Step {
+        let location = self.tower.detours()[0].as_ref().unwrap().location.clone();
+        let coin = self.tower.take_coin();
+        self.coins += coin as u32;
+        self.on_detour = true;
+        self.combo.jump(self.time, self.config.combo_window);
+        self.crumbling = None;
+        self.player = location.clone();
+        Step::Detoured { location, coin }
+    }
+
+    // the platform gone round by a detour scores as if it was climbed, all it holds is missed
+    fn pass(&mut self) -> i32 {
+        let rung = self.tower.pop().unwrap();
+        self.climbed.push(rung);
+        let points = self.score_platform();
+        self.tower.grow();
+        points
+    }
+
+    // true once the crumbling platform the player is on has given way
+    pub fn collapsed(&self) -> bool {
+        self.crumbling.is_some_and(|left| left <= 0.)
+    }
+
+    // drops the player through the crumbling platform, returns where it was,
+    // the player still has to lose_life or fall
+    pub fn collapse(&mut self) -> Location {
+        self.crumbling = None;
+        self.player.clone()
+    }
+
+    // the branch the player is on falls away, it's gone if the player comes back this way
+    // returns its clouds, the player still has to lose_life or fall
+    pub fn crumble(&mut self) -> Vec<Location> {
+        self.on_branch = None;
+        self.tower.crumble_branch()
+    }
+
+    // uses up a life, with one to spare the player goes back to the last hour glass
+    // with a full clock and the tower below it as it was, false when the run is over
+    pub fn lose_life(&mut self) -> bool {
+        self.lives = self.lives.saturating_sub(1);
+        if self.lives == 0 {
+            return false;
+        }
+        self.tower.put_back(self.climbed.drain(..));
+        self.on_branch = None;
+        self.on_detour = false;
+        self.crumbling = None;
+        self.combo.reset();
+        self.player = self.respawn.clone();
+        self.score = self.respawn_score;
+        self.clock.reset();
+        true
+    }
+
+    // advances the checkpoint timer, true once it has run out
+    pub fn tick(&mut self, secs: f32) -> bool {
+        self.time += secs;
+        if let Some(left) = &mut self.crumbling {
+            *left -= secs;
+        }
+        // frozen time holds the time left where it is
+        if self.effects.active(PowerUp::TimeFreeze) {
+            self.clock.extend(secs);
+        }
+        self.clock.tick(secs);
+        self.effects.tick(secs);
+        self.clock.expired()
+    }
+
+    pub fn player(&self) -> &Location {
+        &self.player
+    }
+
+    // where a lost life puts the player back, the last hour glass reached
+    pub fn respawn(&self) -> &Location {
+        &self.respawn
+    }
+
+    pub fn score(&self) -> i32 {
+        self.score.total()
+    }
+
+    // what the score is made up of
+    pub fn score_breakdown(&self) -> &ScoreBreakdown {
+        &self.score
+    }
+
+    pub fn combo(&self) -> &Combo {
+        &self.combo
+    }
+
+    pub fn clock(&self) -> &CheckPointClock {
+        &self.clock
+    }
+
+    pub fn check_point(&self) -> &Location {
+        &self.check_point
+    }
+
+    pub fn check_points_reached(&self) -> u32 {
+        self.check_points_reached
+    }
+
+    pub fn tower(&self) -> &Tower {
+        &self.tower
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn config(&self) -> &ClimbConfig {
+        &self.config
+    }
+
+    pub fn curve(&self) -> &DifficultyCurve {
+        &self.curve
+    }
+
+    pub fn on_branch(&self) -> bool {
+        self.on_branch.is_some()
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    // seconds left on the crumbling platform the player is on
+    pub fn crumbling(&self) -> Option<f32> {
+        self.crumbling
+    }
+
+    pub fn effects(&self) -> &Effects {
+        &self.effects
+    }
+
+    pub fn on_detour(&self) -> bool {
+        self.on_detour
+    }
+
+    // collected this run, kept through lost lives
+    pub fn coins(&self) -> u32 {
+        self.coins
+    }
+}
+
+// what the tower grows from here on, the curve can turn things on but not off
+// pickups and coins wait for the first hour glass so every run opens the same way
+fn set_generation(tower: &mut Tower, config: &ClimbConfig, row: &CurveRow, extras: bool) {
+    tower.set_pickups(if extras {config.pickup_chance} else {0.});
+    tower.set_coins(if extras {config.coin_chance} else {0.});
+    let chance = if config.decoys || row.decoys {config.branch_chance} else {0.};
+    tower.set_branches(chance, config.branch_depth);
+    tower.set_hazards((config.hazards || row.hazards).then(|| config.hazard_weights.clone()));
+}
+
@@ -0,0 +1,51 @@
+pub const CHECK_POINT_TIME: f32 = 20.;
+
+// time left to reach the next hour glass
+pub struct CheckPointClock {
+    duration: f32,
+    elapsed: f32,
+    // frozen seconds added on, until reset
+    extra: f32,
+}
+
+impl Default for CheckPointClock {
+    fn default() -> Self {
+        CheckPointClock::new(CHECK_POINT_TIME)
+    }
+}
+
+impl CheckPointClock {
+    pub fn new(duration: f32) -> Self {
+        CheckPointClock {
+            duration,
+            elapsed: 0.,
+            extra: 0.,
+        }
+    }
+
+    pub fn tick(&mut self, secs: f32) {
+        self.elapsed = (self.elapsed + secs).min(self.duration + self.extra);
+    }
+
+    // pushes the deadline back, elapsed keeps going so replays stay timed by it
+    pub fn extend(&mut self, secs: f32) {
+        self.extra += secs;
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0.;
+        self.extra = 0.;
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn remaining(&self) -> f32 {
+        self.duration + self.extra - self.elapsed
+    }
+
+    pub fn expired(&self) -> bool {
+        self.elapsed >= self.duration + self.extra
+    }
+}
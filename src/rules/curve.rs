@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+// how a run gets harder as the score goes up, each row takes over once the score reaches it
+// rows are read when an hour glass is reached, the shipped table is assets/difficulty_curve.json
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(transparent)]
+pub struct DifficultyCurve(pub Vec<CurveRow>);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct CurveRow {
+    pub score: i32,
+    // the difficulty's check_point_time is multiplied by this
+    pub time_scale: f32,
+    // added to the difficulty's check_point_every
+    pub extra_spacing: usize,
+    // decoys from here on, even on difficulties without them
+    pub decoys: bool,
+    // hazard platforms from here on, the same way
+    pub hazards: bool,
+}
+
+impl Default for CurveRow {
+    fn default() -> Self {
+        CurveRow {
+            score: 0,
+            time_scale: 1.,
+            extra_spacing: 0,
+            decoys: false,
+            hazards: false,
+        }
+    }
+}
+
+impl DifficultyCurve {
+    // the row with the highest score not above this one, an empty curve changes nothing
+    pub fn at(&self, score: i32) -> CurveRow {
+        self.0.iter()
+            .filter(|row| row.score <= score)
+            .max_by_key(|row| row.score)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // a time_scale of zero or below runs the clock out the moment it starts,
+    // and the table is meant to read top to bottom as the score rises
+    pub fn check(&self) -> Result<(), String> {
+        if let Some(row) = self.0.iter().find(|row| row.time_scale.is_nan() || row.time_scale <= 0.) {
+            return Err(format!("the row at score {} has time_scale {}, it needs to be above 0", row.score, row.time_scale));
+        }
+        if let Some(rows) = self.0.windows(2).find(|rows| rows[1].score <= rows[0].score) {
+            return Err(format!("the row at score {} comes after the one at {}, scores need to go up", rows[1].score, rows[0].score));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use crate::rules::{Location, PlatformKind, Y_INC};
+
+// seconds a crumbling platform holds the player
+pub const CRUMBLE_DELAY: f32 = 2.;
+// seconds a spiked platform takes off the clock
+pub const SPIKE_TIME: f32 = 3.;
+// seconds for a drifting platform to go across and back
+pub const DRIFT_PERIOD: f32 = 4.;
+
+// how often each kind of platform is grown relative to the others when hazards are on
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct HazardWeights {
+    pub normal: u32,
+    pub crumbling: u32,
+    pub drifting: u32,
+    pub spiked: u32,
+}
+
+impl Default for HazardWeights {
+    fn default() -> Self {
+        HazardWeights {
+            normal: 12,
+            crumbling: 2,
+            drifting: 2,
+            spiked: 2,
+        }
+    }
+}
+
+impl HazardWeights {
+    pub fn weight(&self, kind: PlatformKind) -> u32 {
+        match kind {
+            PlatformKind::Normal => self.normal,
+            PlatformKind::Crumbling => self.crumbling,
+            PlatformKind::Drifting => self.drifting,
+            PlatformKind::Spiked => self.spiked,
+        }
+    }
+
+    // roll is from 0 up to 1, all weights at zero is all normal
+    pub fn pick(&self, roll: f32) -> PlatformKind {
+        let total: u32 = PlatformKind::ALL.iter().map(|kind| self.weight(*kind)).sum();
+        let mut target = roll * total as f32;
+        for kind in PlatformKind::ALL {
+            let weight = self.weight(kind) as f32;
+            if target < weight {
+                return kind;
+            }
+            target -= weight;
+        }
+        PlatformKind::Normal
+    }
+}
+
+// a drifting platform's two spots, it sits at each for a while and slides between them
+#[derive(Clone, PartialEq, Debug)]
+pub struct Drift {
+    pub home: Location,
+    pub away: Location,
+    // share of a period it's ahead by, so neighbouring clouds don't move together
+    phase: f32,
+}
+
+impl Drift {
+    pub fn new(home: Location, away: Location) -> Self {
+        let phase = (home.y / Y_INC).rem_euclid(4.) / 4.;
+        Drift {
+            home,
+            away,
+            phase,
+        }
+    }
+
+    // 0 at home and 1 away, time is Climb::time
+    pub fn across(&self, time: f32) -> f32 {
+        let turn = (time / DRIFT_PERIOD + self.phase).fract();
+        (0.5 - 1.5 * (std::f32::consts::TAU * turn).cos()).clamp(0., 1.)
+    }
+
+    // where it's drawn
+    pub fn at(&self, time: f32) -> Location {
+        let across = self.across(time);
+        Location {
+            x: self.home.x + (self.away.x - self.home.x) * across,
+            y: self.home.y,
+        }
+    }
+
+    // the spot a jump lands on, whichever side it's closer to
+    pub fn landing(&self, time: f32) -> Location {
+        if self.across(time) > 0.5 {self.away.clone()}
+        else {self.home.clone()}
+    }
+}
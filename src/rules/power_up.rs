@@ -0,0 +1,76 @@
+// seconds each timed power up lasts, picking one up again starts it over
+pub const FREEZE_TIME: f32 = 5.;
+pub const DOUBLER_TIME: f32 = 10.;
+pub const REVEAL_TIME: f32 = 8.;
+
+// what a pickup on a platform does once the player lands there
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PowerUp {
+    // the hour glass timer stops
+    TimeFreeze,
+    // the next wrong step is forgiven, held until then
+    Shield,
+    // two points a platform
+    Doubler,
+    // decoys fade and the path lights up
+    Reveal,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 4] = [PowerUp::TimeFreeze, PowerUp::Shield, PowerUp::Doubler, PowerUp::Reveal];
+
+    // None for the shield, it lasts until used
+    pub fn duration(self) -> Option<f32> {
+        match self {
+            PowerUp::TimeFreeze => Some(FREEZE_TIME),
+            PowerUp::Shield => None,
+            PowerUp::Doubler => Some(DOUBLER_TIME),
+            PowerUp::Reveal => Some(REVEAL_TIME),
+        }
+    }
+
+    // its place in the power_ups.png strip
+    pub fn index(self) -> usize {
+        PowerUp::ALL.iter().position(|power_up| *power_up == self).unwrap()
+    }
+}
+
+// the power ups running on a climb, seconds left for the timed ones
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Effects {
+    time_left: [f32; 4],
+    shield: bool,
+}
+
+impl Effects {
+    pub fn add(&mut self, power_up: PowerUp) {
+        match power_up.duration() {
+            Some(duration) => self.time_left[power_up.index()] = duration,
+            None => self.shield = true,
+        }
+    }
+
+    pub fn tick(&mut self, secs: f32) {
+        for left in &mut self.time_left {
+            *left = (*left - secs).max(0.);
+        }
+    }
+
+    pub fn active(&self, power_up: PowerUp) -> bool {
+        match power_up {
+            PowerUp::Shield => self.shield,
+            _ => self.time_left[power_up.index()] > 0.,
+        }
+    }
+
+    // seconds left on a timed power up that's running
+    pub fn time_left(&self, power_up: PowerUp) -> Option<f32> {
+        let left = self.time_left[power_up.index()];
+        (left > 0.).then_some(left)
+    }
+
+    // true if there was a shield to use up
+    pub fn use_shield(&mut self) -> bool {
+        std::mem::take(&mut self.shield)
+    }
+}
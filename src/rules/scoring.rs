@@ -0,0 +1,55 @@
+// seconds from one jump to the next that keep a combo going
+pub const COMBO_WINDOW: f32 = 0.6;
+// quick jumps in a row for each step up the multiplier, and the most it goes to
+pub const COMBO_STEP: u32 = 5;
+pub const MAX_MULTIPLIER: i32 = 4;
+// points for each whole second left on the clock when an hour glass is reached
+pub const TIME_BONUS: i32 = 1;
+
+// where a run's points came from, the score is their total
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ScoreBreakdown {
+    // a point a platform, two with the doubler
+    pub climbing: i32,
+    // what the combo multiplier added on top
+    pub combo: i32,
+    // for time left over at hour glasses
+    pub time: i32,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> i32 {
+        self.climbing + self.combo + self.time
+    }
+}
+
+// quick jumps in a row, timed on the climb's clock
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Combo {
+    jumps: u32,
+    last: Option<f32>,
+}
+
+impl Combo {
+    // a jump at this time, the streak starts over if the last one was too long ago,
+    // a window of 0 means there are no combos
+    pub fn jump(&mut self, time: f32, window: f32) {
+        let quick = window > 0. && self.last.is_some_and(|last| time - last <= window);
+        self.jumps = if quick {self.jumps + 1} else {1};
+        self.last = Some(time);
+    }
+
+    // after a mistake the next jump starts a new streak
+    pub fn reset(&mut self) {
+        *self = Combo::default();
+    }
+
+    pub fn jumps(&self) -> u32 {
+        self.jumps
+    }
+
+    // what the points for a platform are multiplied by
+    pub fn multiplier(&self) -> i32 {
+        (1 + (self.jumps / COMBO_STEP) as i32).min(MAX_MULTIPLIER)
+    }
+}
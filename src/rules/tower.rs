@@ -0,0 +1,346 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::rules::{Direction, Drift, HazardWeights, Location, PowerUp, X_INC};
+
+const RIGHT_BOUND: f32 = crate::WINDOW_X / 2. - 50.;
+const LEFT_BOUND: f32 = -(crate::WINDOW_X / 2.) + 50.;
+// branches come from their own stream so turning them on doesn't change the correct_path
+const BRANCH_STREAM: u64 = 1;
+const KIND_STREAM: u64 = 2;
+const PICKUP_STREAM: u64 = 3;
+const COIN_STREAM: u64 = 4;
+
+// what a platform does when it's landed on
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum PlatformKind {
+    #[default]
+    Normal,
+    // gives way a little while after the player lands on it
+    Crumbling,
+    // slides across to the other side of the step below and back,
+    // whichever side it's on decides the direction that reaches it
+    Drifting,
+    // costs time off the clock
+    Spiked,
+}
+
+impl PlatformKind {
+    pub const ALL: [PlatformKind; 4] = [PlatformKind::Normal, PlatformKind::Crumbling, PlatformKind::Drifting, PlatformKind::Spiked];
+}
+
+// a cloud off the wrong side of the first of two steps that zigzag,
+// climbing the zigzag the other way round goes over it and back to the second platform
+#[derive(Clone, PartialEq, Debug)]
+pub struct Detour {
+    pub location: Location,
+    // taken when the player lands, the cloud stays
+    pub coin: bool,
+}
+
+// one step of the tower, the platform on it and the way to reach it
+#[derive(Clone, PartialEq, Debug)]
+pub struct Rung {
+    pub direction: Direction,
+    pub platform: Location,
+    // decoy clouds starting on the wrong side, nearest first, empty for most rungs
+    pub branch: Vec<Location>,
+    pub kind: PlatformKind,
+    // taken when the player lands, so it's gone if the rung is put back
+    pub pickup: Option<PowerUp>,
+    pub detour: Option<Detour>,
+}
+
+// the platforms still ahead of the player, nearest first,
+// along with the direction that reaches each one and any decoy branch beside it
+pub struct Tower {
+    rng: ChaCha8Rng,
+    branch_rng: ChaCha8Rng,
+    branch_chance: f64,
+    branch_depth: usize,
+    // the highest branch cloud so far, branches never overlap
+    branch_top: f32,
+    kind_rng: ChaCha8Rng,
+    hazards: Option<HazardWeights>,
+    pickup_rng: ChaCha8Rng,
+    pickup_chance: f32,
+    coin_rng: ChaCha8Rng,
+    coin_chance: f32,
+    // the hour glass platform, never gone round
+    plain: Option<Location>,
+    top: Location,
+    correct_path: Vec<Direction>,
+    platforms: Vec<Location>,
+    branches: Vec<Vec<Location>>,
+    kinds: Vec<PlatformKind>,
+    pickups: Vec<Option<PowerUp>>,
+    detours: Vec<Option<Detour>>,
+}
+
+impl Tower {
+    pub fn new(seed: u64) -> Self {
+        let mut branch_rng = ChaCha8Rng::seed_from_u64(seed);
+        branch_rng.set_stream(BRANCH_STREAM);
+        let mut kind_rng = ChaCha8Rng::seed_from_u64(seed);
+        kind_rng.set_stream(KIND_STREAM);
+        let mut pickup_rng = ChaCha8Rng::seed_from_u64(seed);
+        pickup_rng.set_stream(PICKUP_STREAM);
+        let mut coin_rng = ChaCha8Rng::seed_from_u64(seed);
+        coin_rng.set_stream(COIN_STREAM);
+        Tower {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            branch_rng,
+            branch_chance: 0.,
+            branch_depth: 0,
+            branch_top: f32::MIN,
+            kind_rng,
+            hazards: None,
+            pickup_rng,
+            pickup_chance: 0.,
+            coin_rng,
+            coin_chance: 0.,
+            plain: None,
+            top: Location::default(),
+            correct_path: Vec::new(),
+            platforms: Vec::new(),
+            branches: Vec::new(),
+            kinds: Vec::new(),
+            pickups: Vec::new(),
+            detours: Vec::new(),
+        }
+    }
+
+    // chance is per rung, only affects platforms grown from here on
+    pub fn set_branches(&mut self, chance: f32, depth: usize) {
+        self.branch_chance = chance.clamp(0., 1.) as f64;
+        self.branch_depth = depth;
+    }
+
+    // None keeps every platform normal, only affects platforms grown from here on
+    pub fn set_hazards(&mut self, hazards: Option<HazardWeights>) {
+        self.hazards = hazards;
+    }
+
+    // chance is per rung, only affects platforms grown from here on
+    pub fn set_pickups(&mut self, chance: f32) {
+        self.pickup_chance = chance;
+    }
+
+    // chance is per zigzag, only affects platforms grown from here on
+    pub fn set_coins(&mut self, chance: f32) {
+        self.coin_chance = chance;
+    }
+
+    // adds one platform on top and returns where it is
+    pub fn grow(&mut self) -> Location {
+        let mut dir = self.gen_rand_dir();
+        out_of_bounds(&self.top, &mut dir);
+        // the step after a drifting platform heads back over the step below it,
+        // which is one step away from either side it can be on
+        let after_drift = self.kinds.last() == Some(&PlatformKind::Drifting);
+        if after_drift {
+            dir = self.correct_path.last().unwrap().opposite();
+        }
+        let kind = self.gen_kind(dir, after_drift);
+        // goes off the platform below, so before this rung's branch claims the rows above it
+        let detour = self.gen_detour(dir, kind);
+        if let Some(last) = self.detours.last_mut() {
+            *last = detour;
+        }
+        let branched = self.branch_rng.gen_bool(self.branch_chance);
+        let branch = if branched && kind != PlatformKind::Drifting && !after_drift {
+            self.gen_branch(dir.opposite())
+        }
+        else {
+            Vec::new()
+        };
+        self.top = self.top.step(dir);
+        self.correct_path.push(dir);
+        self.platforms.push(self.top.clone());
+        self.branches.push(branch);
+        self.kinds.push(kind);
+        let pickup = self.gen_pickup();
+        self.pickups.push(pickup.filter(|_| kind != PlatformKind::Drifting));
+        self.detours.push(None);
+        self.top.clone()
+    }
+
+    // off the top platform when this step zigzags back from it and nothing else is in the way,
+    // drifting platforms can't be gone round and neither can the hour glass
+    fn gen_detour(&mut self, dir: Direction, kind: PlatformKind) -> Option<Detour> {
+        let roll: f32 = self.coin_rng.gen();
+        let last = self.platforms.len().checked_sub(1)?;
+        let zigzag = self.correct_path[last] == dir.opposite();
+        let clear = self.branches[last].is_empty()
+            && self.kinds[last] != PlatformKind::Drifting
+            && kind != PlatformKind::Drifting
+            && self.plain.as_ref() != Some(&self.top);
+        let location = across(&self.top, self.correct_path[last]);
+        if roll >= self.coin_chance || !zigzag || !clear || !in_bounds(&location) || location.y <= self.branch_top {
+            return None;
+        }
+        self.branch_top = location.y;
+        Some(Detour {
+            location,
+            coin: true,
+        })
+    }
+
+    fn gen_pickup(&mut self) -> Option<PowerUp> {
+        let roll: f32 = self.pickup_rng.gen();
+        let power_up = PowerUp::ALL[self.pickup_rng.gen_range(0..PowerUp::ALL.len())];
+        (roll < self.pickup_chance).then_some(power_up)
+    }
+
+    // picks by weight, a drifting platform needs the other side clear and inside the window
+    fn gen_kind(&mut self, dir: Direction, after_drift: bool) -> PlatformKind {
+        let roll: f32 = self.kind_rng.gen();
+        let Some(weights) = &self.hazards else {
+            return PlatformKind::Normal;
+        };
+        let kind = weights.pick(roll);
+        let away = self.top.step(dir.opposite());
+        let drift_fits = !after_drift && in_bounds(&away) && away.y > self.branch_top;
+        if kind == PlatformKind::Drifting && !drift_fits {
+            return PlatformKind::Normal;
+        }
+        kind
+    }
+
+    // heads straight away from the correct_path, which can only close in one step at a time,
+    // so a branch never lands on the path or crosses the wall
+    fn gen_branch(&mut self, away: Direction) -> Vec<Location> {
+        let mut branch = Vec::new();
+        let mut loc = self.top.step(away);
+        if loc.y <= self.branch_top {
+            return branch;
+        }
+        while branch.len() < self.branch_depth && in_bounds(&loc) {
+            let next = loc.step(away);
+            branch.push(loc);
+            loc = next;
+        }
+        if let Some(last) = branch.last() {
+            self.branch_top = last.y;
+        }
+        branch
+    }
+
+    pub fn pop(&mut self) -> Option<Rung> {
+        if self.platforms.is_empty() {
+            return None;
+        }
+        Some(Rung {
+            direction: self.correct_path.remove(0),
+            platform: self.platforms.remove(0),
+            branch: self.branches.remove(0),
+            kind: self.kinds.remove(0),
+            pickup: self.pickups.remove(0),
+            detour: self.detours.remove(0),
+        })
+    }
+
+    // puts rungs that were popped back in front, nearest first
+    pub fn put_back(&mut self, climbed: impl IntoIterator<Item = Rung>) {
+        let climbed: Vec<Rung> = climbed.into_iter().collect();
+        self.correct_path.splice(0..0, climbed.iter().map(|rung| rung.direction));
+        self.platforms.splice(0..0, climbed.iter().map(|rung| rung.platform.clone()));
+        self.kinds.splice(0..0, climbed.iter().map(|rung| rung.kind));
+        self.pickups.splice(0..0, climbed.iter().map(|rung| rung.pickup));
+        self.detours.splice(0..0, climbed.iter().map(|rung| rung.detour.clone()));
+        self.branches.splice(0..0, climbed.into_iter().map(|rung| rung.branch));
+    }
+
+    // hour glasses sit on plain clouds with nothing else on them
+    pub fn keep_plain(&mut self, index: usize) {
+        self.kinds[index] = PlatformKind::Normal;
+        self.pickups[index] = None;
+        self.detours[index] = None;
+        self.plain = Some(self.platforms[index].clone());
+    }
+
+    // the next rung's detour coin, false if it was already taken
+    pub fn take_coin(&mut self) -> bool {
+        self.detours.first_mut()
+            .and_then(Option::as_mut)
+            .is_some_and(|detour| std::mem::take(&mut detour.coin))
+    }
+
+    // the next rung's branch falls away
+    pub fn crumble_branch(&mut self) -> Vec<Location> {
+        self.branches.first_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    pub fn next_direction(&self) -> Option<Direction> {
+        self.correct_path.first().copied()
+    }
+
+    pub fn top(&self) -> &Location {
+        &self.top
+    }
+
+    pub fn correct_path(&self) -> &[Direction] {
+        &self.correct_path
+    }
+
+    pub fn platforms(&self) -> &[Location] {
+        &self.platforms
+    }
+
+    // lines up with platforms, the decoy branch off the wrong side of each
+    pub fn branches(&self) -> &[Vec<Location>] {
+        &self.branches
+    }
+
+    // lines up with platforms
+    pub fn kinds(&self) -> &[PlatformKind] {
+        &self.kinds
+    }
+
+    // lines up with platforms
+    pub fn pickups(&self) -> &[Option<PowerUp>] {
+        &self.pickups
+    }
+
+    // lines up with platforms, the way round the first of each zigzag
+    pub fn detours(&self) -> &[Option<Detour>] {
+        &self.detours
+    }
+
+    // where a drifting platform slides between, None for every other kind
+    pub fn drift(&self, index: usize) -> Option<Drift> {
+        if self.kinds[index] != PlatformKind::Drifting {
+            return None;
+        }
+        // mirrored over the platform it's stepped up to from
+        let home = self.platforms[index].clone();
+        let away = across(&home, self.correct_path[index]);
+        Some(Drift::new(home, away))
+    }
+
+    fn gen_rand_dir(&mut self) -> Direction {
+        if self.rng.gen() {Direction::Right}
+        else {Direction::Left}
+    }
+}
+
+fn out_of_bounds(loc: &Location, dir: &mut Direction) {
+    if loc.x + X_INC > RIGHT_BOUND {
+        *dir = Direction::Left;
+    }
+    if loc.x - X_INC < LEFT_BOUND {
+        *dir = Direction::Right;
+    }
+}
+
+// level with a platform on the other side of the one below it, dir is the step up to it
+fn across(loc: &Location, dir: Direction) -> Location {
+    let x = match dir {
+        Direction::Left => loc.x + 2. * X_INC,
+        Direction::Right => loc.x - 2. * X_INC,
+    };
+    Location { x, y: loc.y }
+}
+
+fn in_bounds(loc: &Location) -> bool {
+    loc.x <= RIGHT_BOUND && loc.x >= LEFT_BOUND
+}
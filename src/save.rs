@@ -0,0 +1,272 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::settings::{Settings, SETTINGS_NAME};
+use crate::rules::Difficulty;
+use crate::shop::{CloudSkin, Cosmetic, PlayerSkin};
+
+const SAVE_VERSION: u32 = 2;
+const SAVE_NAME: &str = "save.json";
+
+// in_memory keeps everything for the life of the app only, used by the headless harness
+#[derive(Default)]
+pub struct SavePlugin {
+    pub in_memory: bool,
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App){
+        let storage = if self.in_memory {
+            Storage::in_memory()
+        }
+        else {
+            Storage::default()
+        };
+        app
+            .insert_resource(SaveData::load(&storage))
+            .insert_resource(load_json::<Settings>(&storage, SETTINGS_NAME))
+            .insert_resource(storage)
+            .add_systems(Last, (
+                store_save
+                    .run_if(resource_changed::<SaveData>)
+                    .run_if(not(resource_added::<SaveData>)),
+                store_settings
+                    .run_if(resource_changed::<Settings>)
+                    .run_if(not(resource_added::<Settings>)),
+            ));
+    }
+}
+
+// everything that should outlive a single session
+// new fields need a serde default so older saves still load
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    // each difficulty keeps its own table
+    pub high_scores: BTreeMap<Difficulty, i32>,
+    // best daily challenge score keyed by YYYY-MM-DD
+    pub daily_best: BTreeMap<String, i32>,
+    // banked at the end of each run, spent in the shop
+    pub coins: u32,
+    pub unlocked: BTreeSet<Cosmetic>,
+    pub player_skin: PlayerSkin,
+    pub cloud_skin: CloudSkin,
+    // version 1 had a single high score, it becomes the normal one
+    #[serde(rename = "high_score", skip_serializing)]
+    old_high_score: i32,
+    // set when the file is from a newer version, it's played with but never written over
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        SaveData {
+            version: SAVE_VERSION,
+            high_scores: BTreeMap::new(),
+            daily_best: BTreeMap::new(),
+            coins: 0,
+            unlocked: BTreeSet::new(),
+            player_skin: PlayerSkin::default(),
+            cloud_skin: CloudSkin::default(),
+            old_high_score: 0,
+            read_only: false,
+        }
+    }
+}
+
+impl SaveData {
+    pub fn load(storage: &Storage) -> Self {
+        let save = load_json::<SaveData>(storage, SAVE_NAME);
+        if save.version > SAVE_VERSION {
+            warn!("save data version {} is newer than {}, starting fresh without saving over it", save.version, SAVE_VERSION);
+            return SaveData {
+                read_only: true,
+                ..SaveData::default()
+            };
+        }
+        let mut save = SaveData {
+            version: SAVE_VERSION,
+            ..save
+        };
+        let old_high_score = std::mem::take(&mut save.old_high_score);
+        save.record_high_score(Difficulty::Normal, old_high_score);
+        save
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn high_score(&self, difficulty: Difficulty) -> i32 {
+        self.high_scores.get(&difficulty).copied().unwrap_or_default()
+    }
+
+    pub fn record_high_score(&mut self, difficulty: Difficulty, score: i32) {
+        if score > self.high_score(difficulty) {
+            self.high_scores.insert(difficulty, score);
+        }
+    }
+
+    pub fn daily_best(&self, date: &str) -> i32 {
+        self.daily_best.get(date).copied().unwrap_or_default()
+    }
+
+    pub fn record_daily(&mut self, date: &str, score: i32) {
+        if score > self.daily_best(date) {
+            self.daily_best.insert(date.to_string(), score);
+        }
+    }
+
+    pub fn owns(&self, item: Cosmetic) -> bool {
+        item.price() == 0 || self.unlocked.contains(&item)
+    }
+
+    // false if it's already owned or there aren't the coins for it
+    pub fn buy(&mut self, item: Cosmetic) -> bool {
+        if self.owns(item) || self.coins < item.price() {
+            return false;
+        }
+        self.coins -= item.price();
+        self.unlocked.insert(item);
+        true
+    }
+
+    // only owned things can be worn
+    pub fn wear(&mut self, item: Cosmetic) {
+        if !self.owns(item) {
+            return;
+        }
+        match item {
+            Cosmetic::Player(skin) => self.player_skin = skin,
+            Cosmetic::Cloud(skin) => self.cloud_skin = skin,
+        }
+    }
+
+    pub fn wearing(&self, item: Cosmetic) -> bool {
+        match item {
+            Cosmetic::Player(skin) => self.player_skin == skin,
+            Cosmetic::Cloud(skin) => self.cloud_skin == skin,
+        }
+    }
+
+}
+
+fn store_save(save: Res<SaveData>, mut storage: ResMut<Storage>) {
+    if save.read_only {
+        return;
+    }
+    store_json(&mut storage, SAVE_NAME, &*save);
+}
+
+fn store_settings(settings: Res<Settings>, mut storage: ResMut<Storage>) {
+    store_json(&mut storage, SETTINGS_NAME, &*settings);
+}
+
+// missing files give the default, so do corrupt ones after a warning
+pub fn load_json<T: DeserializeOwned + Default>(storage: &Storage, name: &str) -> T {
+    let Some(contents) = storage.read(name) else {
+        return T::default();
+    };
+    match serde_json::from_str(&contents) {
+        Ok(value) => value,
+        Err(err) => {
+            warn!("{name} is corrupt, starting fresh: {err}");
+            T::default()
+        },
+    }
+}
+
+pub fn store_json<T: Serialize>(storage: &mut Storage, name: &str, value: &T) {
+    let contents = match serde_json::to_string(value) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("could not serialize {name}: {err}");
+            return;
+        },
+    };
+    if let Err(err) = storage.write(name, &contents) {
+        warn!("could not write {name}: {err}");
+    }
+}
+
+// where saved files go, the platform backend unless it's in memory
+#[derive(Resource, Default)]
+pub struct Storage {
+    memory: Option<HashMap<String, String>>,
+}
+
+impl Storage {
+    pub fn in_memory() -> Self {
+        Storage {
+            memory: Some(HashMap::new()),
+        }
+    }
+
+    pub fn read(&self, name: &str) -> Option<String> {
+        match &self.memory {
+            Some(memory) => memory.get(name).cloned(),
+            None => backend::read(name),
+        }
+    }
+
+    pub fn write(&mut self, name: &str, contents: &str) -> Result<(), String> {
+        match &mut self.memory {
+            Some(memory) => {
+                memory.insert(name.to_string(), contents.to_string());
+                Ok(())
+            },
+            None => backend::write(name, contents),
+        }
+    }
+}
+
+// native builds keep one file per name in the platform data directory
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn path(name: &str) -> Option<PathBuf> {
+        Some(dirs::data_dir()?.join("uppy-up").join(name))
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        fs::read_to_string(path(name)?).ok()
+    }
+
+    pub fn write(name: &str, contents: &str) -> Result<(), String> {
+        let path = path(name).ok_or("no data directory on this platform")?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+        }
+        // write then rename so a crash mid-write can't leave a half written save
+        let tmp = path.with_file_name(format!("{name}.tmp"));
+        fs::write(&tmp, contents).map_err(|err| err.to_string())?;
+        fs::rename(&tmp, &path).map_err(|err| err.to_string())
+    }
+}
+
+// the web build keeps the same contents in localStorage
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    fn key(name: &str) -> String {
+        format!("uppy-up.{name}")
+    }
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        local_storage()?.get_item(&key(name)).ok()?
+    }
+
+    pub fn write(name: &str, contents: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("localStorage is unavailable")?
+            .set_item(&key(name), contents)
+            .map_err(|err| format!("{err:?}"))
+    }
+}
@@ -0,0 +1,483 @@
+use bevy::prelude::*;
+use crate::assets::GameAssets;
+use bevy::ui::FocusPolicy;
+use serde::{Deserialize, Serialize};
+use bevy::window::{PrimaryWindow, WindowMode};
+use crate::audio::SoundKind;
+use crate::rules::{ClimbConfig, ClimbOption, Difficulty, Direction};
+use crate::ui::{self, BACKGROUND, BLUE, PURPLE};
+
+pub const SETTINGS_NAME: &str = "settings.json";
+
+// three columns have to fit across the window
+const COLUMN_WIDTH: f32 = 240.;
+// what the lives button cycles through, one is the classic game
+const LIVES: [u32; 3] = [1, 3, 5];
+
+// player preferences, saved next to the save data by save::SavePlugin
+// new fields need a serde default so older settings still load
+#[derive(Resource, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub bindings: KeyBindings,
+    pub audio: AudioSettings,
+    pub fullscreen: bool,
+    // picked on the menu, daily runs always play normal
+    pub difficulty: Difficulty,
+    // the custom difficulty, and the lives every difficulty uses
+    pub climb: ClimbConfig,
+}
+
+// volumes run from 0 to 1
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings {
+            master: 1.,
+            sfx: 1.,
+            music: 0.7,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    // what a sound of the given kind should actually play at
+    pub fn volume(&self, kind: SoundKind) -> f32 {
+        if self.muted {
+            return 0.;
+        }
+        let channel = match kind {
+            SoundKind::Sfx => self.sfx,
+            SoundKind::Music => self.music,
+        };
+        self.master * channel
+    }
+
+    pub fn channel(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Master => self.master,
+            Channel::Sfx => self.sfx,
+            Channel::Music => self.music,
+        }
+    }
+
+    fn channel_mut(&mut self, channel: Channel) -> &mut f32 {
+        match channel {
+            Channel::Master => &mut self.master,
+            Channel::Sfx => &mut self.sfx,
+            Channel::Music => &mut self.music,
+        }
+    }
+
+    // steps are tenths, kept whole so repeated clicks don't drift
+    pub fn step(&mut self, channel: Channel, step: i32) {
+        let volume = self.channel_mut(channel);
+        *volume = ((*volume * 10.).round() + step as f32).clamp(0., 10.) / 10.;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Channel {
+    Master,
+    Sfx,
+    Music,
+}
+
+// two keys per direction, the second one is the alternative
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct KeyBindings {
+    pub left: [KeyCode; 2],
+    pub right: [KeyCode; 2],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            left: [KeyCode::KeyA, KeyCode::ArrowLeft],
+            right: [KeyCode::KeyD, KeyCode::ArrowRight],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, direction: Direction) -> &[KeyCode; 2] {
+        match direction {
+            Direction::Left => &self.left,
+            Direction::Right => &self.right,
+        }
+    }
+
+    fn keys_mut(&mut self, direction: Direction) -> &mut [KeyCode; 2] {
+        match direction {
+            Direction::Left => &mut self.left,
+            Direction::Right => &mut self.right,
+        }
+    }
+
+    // binding a key that's already in use swaps the two
+    pub fn bind(&mut self, direction: Direction, slot: usize, key: KeyCode) {
+        let old = self.keys(direction)[slot];
+        for other in [Direction::Left, Direction::Right] {
+            for bound in self.keys_mut(other) {
+                if *bound == key {
+                    *bound = old;
+                }
+            }
+        }
+        self.keys_mut(direction)[slot] = key;
+    }
+
+    // the menu's how to play line
+    pub fn instructions(&self) -> String {
+        format!(
+            "Use ({}, {}) or ({}, {}) to go up. \
+            Move in the wrong direction or run out of time and it's game over. \
+            Reach an hour glass to earn more time.",
+            key_name(self.left[0]), key_name(self.right[0]),
+            key_name(self.left[1]), key_name(self.right[1]),
+        )
+    }
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match key {
+        KeyCode::ArrowLeft => String::from("←"),
+        KeyCode::ArrowRight => String::from("→"),
+        KeyCode::ArrowUp => String::from("↑"),
+        KeyCode::ArrowDown => String::from("↓"),
+        _ => name
+            .strip_prefix("Key")
+            .or_else(|| name.strip_prefix("Digit"))
+            .map(str::to_lowercase)
+            .unwrap_or(name),
+    }
+}
+
+// the settings screen opens over whatever state the game is in
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+pub enum SettingsScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum SettingsButton {
+    Bind(Direction, usize),
+    // one step down or up
+    Volume(Channel, i32),
+    Mute,
+    Fullscreen,
+    Lives,
+    // changing any custom option picks the custom difficulty
+    Custom(ClimbOption, i32),
+    Decoys,
+    Hazards,
+    Back,
+}
+
+#[derive(Component)]
+struct VolumeLabel(Channel);
+
+#[derive(Component)]
+struct CustomLabel(ClimbOption);
+
+// the binding waiting for a key press
+#[derive(Resource, Default)]
+struct Rebinding(Option<(Direction, usize)>);
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .init_state::<SettingsScreen>()
+            .enable_state_scoped_entities::<SettingsScreen>()
+            .init_resource::<Rebinding>()
+            .add_systems(OnEnter(SettingsScreen::Open), load_settings_screen)
+            .add_systems(OnExit(SettingsScreen::Open), cancel_rebinding)
+            .add_systems(Update, (
+                button_system,
+                capture_key,
+                update_labels,
+            )
+                .chain()
+                .run_if(in_state(SettingsScreen::Open)))
+            .add_systems(Update, apply_display.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn load_settings_screen(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        background_color: BackgroundColor(BACKGROUND),
+        focus_policy: FocusPolicy::Block,
+        z_index: ZIndex::Global(10),
+        ..default()
+    }, StateScoped(SettingsScreen::Open)))
+    .with_children(|parent|{
+        ui::spawn_text(parent, &assets, "SETTINGS", 40.);
+        parent.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(20.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent|{
+            ui::spawn_column(parent, AlignItems::Center).with_children(|parent|{
+                ui::spawn_text(parent, &assets, "CONTROLS", 25.);
+                for direction in [Direction::Left, Direction::Right] {
+                    for slot in 0..2 {
+                        ui::spawn_small_button(parent, &assets, SettingsButton::Bind(direction, slot), COLUMN_WIDTH, 20.);
+                    }
+                }
+            });
+            ui::spawn_column(parent, AlignItems::Center).with_children(|parent|{
+                ui::spawn_text(parent, &assets, "AUDIO & DISPLAY", 25.);
+                for channel in [Channel::Master, Channel::Sfx, Channel::Music] {
+                    spawn_volume_row(parent, &assets, channel);
+                }
+                ui::spawn_small_button(parent, &assets, SettingsButton::Mute, COLUMN_WIDTH, 20.);
+                ui::spawn_small_button(parent, &assets, SettingsButton::Fullscreen, COLUMN_WIDTH, 20.);
+            });
+            ui::spawn_column(parent, AlignItems::Center).with_children(|parent|{
+                ui::spawn_text(parent, &assets, "GAMEPLAY", 25.);
+                ui::spawn_small_button(parent, &assets, SettingsButton::Lives, COLUMN_WIDTH, 20.);
+                for option in [ClimbOption::CheckPointTime, ClimbOption::CheckPointEvery, ClimbOption::Lookahead] {
+                    spawn_step_row(parent, &assets, SettingsButton::Custom(option, -1), SettingsButton::Custom(option, 1), CustomLabel(option));
+                }
+                spawn_toggle_row(parent, &assets, SettingsButton::Decoys, SettingsButton::Hazards);
+                for option in [ClimbOption::BranchChance, ClimbOption::BranchDepth] {
+                    spawn_step_row(parent, &assets, SettingsButton::Custom(option, -1), SettingsButton::Custom(option, 1), CustomLabel(option));
+                }
+            });
+        });
+        ui::spawn_small_button(parent, &assets, SettingsButton::Back, 300., 20.);
+    });
+}
+
+// - Master: 100% +
+fn spawn_volume_row(parent: &mut ChildBuilder, assets: &GameAssets, channel: Channel) {
+    spawn_step_row(parent, assets, SettingsButton::Volume(channel, -1), SettingsButton::Volume(channel, 1), VolumeLabel(channel));
+}
+
+fn spawn_step_row(parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    down: SettingsButton,
+    up: SettingsButton,
+    label: impl Component,
+) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Px(COLUMN_WIDTH),
+            height: Val::Px(50.0),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    })
+    .with_children(|parent|{
+        ui::spawn_small_button(parent, assets, down, 50., 20.);
+        parent.spawn((TextBundle::from_section(
+            ""
+            , TextStyle {
+                font: assets.font.clone(),
+                font_size: 20.,
+                color: Color::WHITE,
+            }
+        ), label));
+        ui::spawn_small_button(parent, assets, up, 50., 20.);
+    });
+}
+
+// two on/off buttons side by side, with smaller labels to fit
+fn spawn_toggle_row(parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    left: SettingsButton,
+    right: SettingsButton,
+) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Px(COLUMN_WIDTH),
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::SpaceBetween,
+            ..default()
+        },
+        ..default()
+    })
+    .with_children(|parent|{
+        let width = (COLUMN_WIDTH - 10.) / 2.;
+        ui::spawn_small_button(parent, assets, left, width, 16.);
+        ui::spawn_small_button(parent, assets, right, width, 16.);
+    });
+}
+
+fn cancel_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &SettingsButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+    mut screen: ResMut<NextState<SettingsScreen>>,
+)
+{
+    for (interaction, mut background_color, button) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = BackgroundColor(BLUE);
+                match button {
+                    SettingsButton::Bind(direction, slot) => rebinding.0 = Some((*direction, *slot)),
+                    SettingsButton::Volume(channel, step) => settings.audio.step(*channel, *step),
+                    SettingsButton::Mute => settings.audio.muted = !settings.audio.muted,
+                    SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+                    SettingsButton::Lives => settings.climb.lives = next_lives(settings.climb.lives),
+                    SettingsButton::Custom(option, step) => {
+                        settings.climb.step(*option, *step);
+                        settings.difficulty = Difficulty::Custom;
+                    },
+                    SettingsButton::Decoys => {
+                        settings.climb.decoys = !settings.climb.decoys;
+                        settings.difficulty = Difficulty::Custom;
+                    },
+                    SettingsButton::Hazards => {
+                        settings.climb.hazards = !settings.climb.hazards;
+                        settings.difficulty = Difficulty::Custom;
+                    },
+                    SettingsButton::Back => screen.set(SettingsScreen::Closed),
+                }
+            },
+            Interaction::Hovered => *background_color = BackgroundColor(BLUE),
+            Interaction::None => *background_color = BackgroundColor(PURPLE),
+        }
+    }
+}
+
+// anything off the list goes back to the start of it
+pub fn next_lives(lives: u32) -> u32 {
+    LIVES.iter()
+        .position(|option| *option == lives)
+        .map_or(LIVES[0], |index| LIVES[(index + 1) % LIVES.len()])
+}
+
+// escape cancels a rebind, or closes the screen when nothing is being bound
+fn capture_key(keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut settings: ResMut<Settings>,
+    mut screen: ResMut<NextState<SettingsScreen>>,
+)
+{
+    let Some(key) = keyboard_input.get_just_pressed().next().copied() else {
+        return;
+    };
+    match rebinding.0.take() {
+        Some(_) if key == KeyCode::Escape => {},
+        Some((direction, slot)) => settings.bindings.bind(direction, slot, key),
+        None if key == KeyCode::Escape => screen.set(SettingsScreen::Closed),
+        None => {},
+    }
+}
+
+fn update_labels(buttons: Query<(&SettingsButton, &Children)>,
+    volume_labels: Query<(Entity, &VolumeLabel)>,
+    custom_labels: Query<(Entity, &CustomLabel)>,
+    mut texts: Query<&mut Text>,
+    settings: Res<Settings>,
+    rebinding: Res<Rebinding>,
+)
+{
+    let on_off = |on: bool| if on {"On"} else {"Off"};
+    for (button, children) in &buttons {
+        let label = match button {
+            SettingsButton::Bind(direction, slot) => {
+                let name = match direction {
+                    Direction::Left => "Left",
+                    Direction::Right => "Right",
+                };
+                let alt = if *slot == 0 {""} else {" (alt)"};
+                if rebinding.0 == Some((*direction, *slot)) {
+                    format!("{name}{alt}: press a key")
+                }
+                else {
+                    format!("{name}{alt}: {}", key_name(settings.bindings.keys(*direction)[*slot]))
+                }
+            },
+            SettingsButton::Volume(_, step) | SettingsButton::Custom(_, step) => String::from(if *step < 0 {"-"} else {"+"}),
+            SettingsButton::Mute => format!("Mute: {}", on_off(settings.audio.muted)),
+            SettingsButton::Fullscreen => format!("Fullscreen: {}", on_off(settings.fullscreen)),
+            SettingsButton::Lives => format!("Lives: {}", settings.climb.lives),
+            SettingsButton::Decoys => format!("Decoys: {}", on_off(settings.climb.decoys)),
+            SettingsButton::Hazards => format!("Hazards: {}", on_off(settings.climb.hazards)),
+            SettingsButton::Back => String::from("BACK"),
+        };
+        for child in children {
+            set_text(&mut texts, *child, &label);
+        }
+    }
+    for (entity, VolumeLabel(channel)) in &volume_labels {
+        let name = match channel {
+            Channel::Master => "Master",
+            Channel::Sfx => "Effects",
+            Channel::Music => "Music",
+        };
+        let label = format!("{name}: {}%", (settings.audio.channel(*channel) * 100.).round());
+        set_text(&mut texts, entity, &label);
+    }
+    for (entity, CustomLabel(option)) in &custom_labels {
+        let climb = &settings.climb;
+        let label = match option {
+            ClimbOption::CheckPointTime => format!("Timer: {}s", climb.check_point_time),
+            ClimbOption::CheckPointEvery => format!("Hourglass: {}", climb.check_point_every),
+            ClimbOption::Lookahead => format!("Ahead: {}", climb.lookahead),
+            ClimbOption::BranchChance => format!("Branches: {}%", (climb.branch_chance * 100.).round()),
+            ClimbOption::BranchDepth => format!("Branch length: {}", climb.branch_depth),
+        };
+        set_text(&mut texts, entity, &label);
+    }
+}
+
+fn set_text(texts: &mut Query<&mut Text>, entity: Entity, label: &str) {
+    if let Ok(mut text) = texts.get_mut(entity) {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.to_string();
+        }
+    }
+}
+
+fn apply_display(settings: Res<Settings>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen
+    }
+    else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use serde::{Deserialize, Serialize};
+use crate::assets::GameAssets;
+use crate::save::SaveData;
+use crate::ui::{self, BACKGROUND, BLUE, PURPLE};
+
+const ITEM_WIDTH: f32 = 220.;
+
+// player sprite sheets, all laid out like ball_guy.png
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum PlayerSkin {
+    #[default]
+    BallGuy,
+    Lime,
+    Ember,
+}
+
+impl PlayerSkin {
+    pub const ALL: [PlayerSkin; 3] = [PlayerSkin::BallGuy, PlayerSkin::Lime, PlayerSkin::Ember];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            PlayerSkin::BallGuy => "ball_guy.png",
+            PlayerSkin::Lime => "ball_guy_lime.png",
+            PlayerSkin::Ember => "ball_guy_ember.png",
+        }
+    }
+}
+
+// the plain cloud, hazards keep their own look so they can still be read
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub enum CloudSkin {
+    #[default]
+    Classic,
+    Candy,
+    Storm,
+    Gold,
+}
+
+impl CloudSkin {
+    pub const ALL: [CloudSkin; 4] = [CloudSkin::Classic, CloudSkin::Candy, CloudSkin::Storm, CloudSkin::Gold];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            CloudSkin::Classic => "cloud.png",
+            CloudSkin::Candy => "cloud_candy.png",
+            CloudSkin::Storm => "cloud_storm.png",
+            CloudSkin::Gold => "cloud_gold.png",
+        }
+    }
+}
+
+// anything the shop sells, the defaults are free and always owned
+#[derive(Serialize, Deserialize, Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Cosmetic {
+    Player(PlayerSkin),
+    Cloud(CloudSkin),
+}
+
+impl Cosmetic {
+    pub fn price(self) -> u32 {
+        match self {
+            Cosmetic::Player(PlayerSkin::BallGuy) | Cosmetic::Cloud(CloudSkin::Classic) => 0,
+            Cosmetic::Player(PlayerSkin::Lime) => 30,
+            Cosmetic::Player(PlayerSkin::Ember) => 60,
+            Cosmetic::Cloud(CloudSkin::Candy) => 20,
+            Cosmetic::Cloud(CloudSkin::Storm) => 40,
+            Cosmetic::Cloud(CloudSkin::Gold) => 100,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Cosmetic::Player(PlayerSkin::BallGuy) => "Ball Guy",
+            Cosmetic::Player(PlayerSkin::Lime) => "Lime",
+            Cosmetic::Player(PlayerSkin::Ember) => "Ember",
+            Cosmetic::Cloud(CloudSkin::Classic) => "Classic",
+            Cosmetic::Cloud(CloudSkin::Candy) => "Candy",
+            Cosmetic::Cloud(CloudSkin::Storm) => "Storm",
+            Cosmetic::Cloud(CloudSkin::Gold) => "Gold",
+        }
+    }
+}
+
+// the shop opens over the menu
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, States)]
+pub enum ShopScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub enum ShopButton {
+    // buys it if need be, then puts it on
+    Item(Cosmetic),
+    Back,
+}
+
+#[derive(Component)]
+struct BalanceText;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App){
+        app
+            .init_state::<ShopScreen>()
+            .enable_state_scoped_entities::<ShopScreen>()
+            .add_systems(OnEnter(ShopScreen::Open), load_shop_screen)
+            .add_systems(Update, (
+                button_system,
+                close_on_escape,
+                update_labels,
+            )
+                .chain()
+                .run_if(in_state(ShopScreen::Open)))
+            .add_systems(PreUpdate, wear_skins.run_if(resource_changed::<SaveData>));
+    }
+}
+
+// spawn sites take the player and cloud handles, so they're swapped for the ones put on
+fn wear_skins(save: Res<SaveData>, mut assets: ResMut<GameAssets>) {
+    if let Some(player) = assets.player_skins.get(&save.player_skin).cloned() {
+        assets.player = player;
+    }
+    if let Some(cloud) = assets.cloud_skins.get(&save.cloud_skin).cloned() {
+        assets.cloud = cloud;
+    }
+}
+
+fn load_shop_screen(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((NodeBundle {
+        style: Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        background_color: BackgroundColor(BACKGROUND),
+        focus_policy: FocusPolicy::Block,
+        z_index: ZIndex::Global(10),
+        ..default()
+    }, StateScoped(ShopScreen::Open)))
+    .with_children(|parent|{
+        ui::spawn_text(parent, &assets, "SHOP", 40.);
+        parent.spawn((TextBundle::from_section(
+            ""
+            , TextStyle {
+                font: assets.font.clone(),
+                font_size: 25.,
+                color: Color::WHITE,
+            }
+        ), BalanceText));
+        parent.spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(40.),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent|{
+            ui::spawn_column(parent, AlignItems::End).with_children(|parent|{
+                ui::spawn_text(parent, &assets, "PLAYERS", 25.);
+                for skin in PlayerSkin::ALL {
+                    spawn_item(parent, &assets, Cosmetic::Player(skin));
+                }
+            });
+            ui::spawn_column(parent, AlignItems::End).with_children(|parent|{
+                ui::spawn_text(parent, &assets, "CLOUDS", 25.);
+                for skin in CloudSkin::ALL {
+                    spawn_item(parent, &assets, Cosmetic::Cloud(skin));
+                }
+            });
+        });
+        ui::spawn_small_button(parent, &assets, ShopButton::Back, 300., 20.);
+    });
+}
+
+// a preview of it next to the button that buys or wears it
+fn spawn_item(parent: &mut ChildBuilder, assets: &GameAssets, item: Cosmetic) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(10.),
+            ..default()
+        },
+        ..default()
+    })
+    .with_children(|parent|{
+        match item {
+            Cosmetic::Player(skin) => {
+                parent.spawn((ImageBundle {
+                    style: Style {
+                        width: Val::Px(48.),
+                        height: Val::Px(48.),
+                        ..default()
+                    },
+                    image: UiImage::new(assets.player_skins[&skin].clone()),
+                    ..default()
+                },
+                TextureAtlas {
+                    layout: assets.player_layout.clone(),
+                    index: 0,
+                }));
+            },
+            Cosmetic::Cloud(skin) => {
+                parent.spawn(ImageBundle {
+                    style: Style {
+                        width: Val::Px(72.),
+                        height: Val::Px(18.),
+                        ..default()
+                    },
+                    image: UiImage::new(assets.cloud_skins[&skin].clone()),
+                    ..default()
+                });
+            },
+        }
+        ui::spawn_small_button(parent, assets, ShopButton::Item(item), ITEM_WIDTH, 20.);
+    });
+}
+
+fn button_system(mut interaction_query: Query<(&Interaction, &mut BackgroundColor, &ShopButton), Changed<Interaction>>,
+    mut save: ResMut<SaveData>,
+    mut screen: ResMut<NextState<ShopScreen>>,
+)
+{
+    for (interaction, mut background_color, button) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *background_color = BackgroundColor(BLUE);
+                match button {
+                    ShopButton::Item(item) => {
+                        // too dear leaves things as they were
+                        if save.owns(*item) || save.buy(*item) {
+                            save.wear(*item);
+                        }
+                    },
+                    ShopButton::Back => screen.set(ShopScreen::Closed),
+                }
+            },
+            Interaction::Hovered => *background_color = BackgroundColor(BLUE),
+            Interaction::None => *background_color = BackgroundColor(PURPLE),
+        }
+    }
+}
+
+fn close_on_escape(keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut screen: ResMut<NextState<ShopScreen>>,
+)
+{
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        screen.set(ShopScreen::Closed);
+    }
+}
+
+// Lime: 30 until it's bought, then Lime, and Lime (on) while it's worn
+pub fn item_label(save: &SaveData, item: Cosmetic) -> String {
+    if save.wearing(item) {
+        format!("{} (on)", item.name())
+    }
+    else if save.owns(item) {
+        item.name().to_string()
+    }
+    else {
+        format!("{}: {}", item.name(), item.price())
+    }
+}
+
+fn update_labels(buttons: Query<(&ShopButton, &Children)>,
+    mut balance: Query<&mut Text, With<BalanceText>>,
+    mut texts: Query<&mut Text, Without<BalanceText>>,
+    save: Res<SaveData>,
+)
+{
+    for mut text in &mut balance {
+        let label = format!("Coins: {}", save.coins);
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+    for (button, children) in &buttons {
+        let label = match button {
+            ShopButton::Item(item) => item_label(&save, *item),
+            ShopButton::Back => String::from("BACK"),
+        };
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+}
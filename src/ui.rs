@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy::ecs::system::EntityCommands;
+use crate::assets::GameAssets;
+
+// buttons sit purple, or pink on the game over screen, and go blue under the pointer
+pub const BLUE: Color = Color::srgb(0.0,0.67,1.0);
+pub const PINK: Color = Color::srgb(1.0,0.67,1.0);
+pub const PURPLE: Color = Color::srgb(0.69, 0.67, 1.0);
+// behind the screens drawn over the menu or a run
+pub const BACKGROUND: Color = Color::srgba(0.1, 0.1, 0.2, 0.9);
+
+pub fn spawn_text(parent: &mut ChildBuilder, assets: &GameAssets, text: &str, font_size: f32) {
+    parent.spawn(TextBundle::from_section(
+        text
+        , TextStyle {
+            font: assets.font.clone(),
+            font_size,
+            color: Color::WHITE,
+        }
+    ));
+}
+
+// the big buttons of the menu, pause and game over screens
+pub fn spawn_button(parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    label: &str,
+    button: impl Component,
+    width: f32,
+    color: Color,
+) {
+    spawn_rounded(parent, button, width, 65., color)
+    .with_children(|parent|{
+        spawn_text(parent, assets, label, 40.);
+    });
+}
+
+// the settings and shop buttons, their labels are filled in by the screen's own systems
+pub fn spawn_small_button(parent: &mut ChildBuilder,
+    assets: &GameAssets,
+    button: impl Component,
+    width: f32,
+    font_size: f32,
+) {
+    spawn_rounded(parent, button, width, 50., PURPLE)
+    .with_children(|parent|{
+        spawn_text(parent, assets, "", font_size);
+    });
+}
+
+fn spawn_rounded<'a>(parent: &'a mut ChildBuilder,
+    button: impl Component,
+    width: f32,
+    height: f32,
+    color: Color,
+) -> EntityCommands<'a>
+{
+    parent.spawn((ButtonBundle {
+        style: Style {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            border: UiRect::all(Val::Px(5.0)),
+            align_content: AlignContent::Center,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BackgroundColor(color),
+        border_color: BorderColor(Color::BLACK),
+        border_radius: BorderRadius::MAX,
+        ..default()
+    }, button))
+}
+
+// a titled column of rows, side by side with the others on the screen
+pub fn spawn_column<'a>(parent: &'a mut ChildBuilder, align_items: AlignItems) -> EntityCommands<'a> {
+    parent.spawn(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            align_items,
+            row_gap: Val::Px(10.),
+            ..default()
+        },
+        ..default()
+    })
+}
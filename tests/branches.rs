@@ -0,0 +1,152 @@
+mod common;
+
+use bevy::prelude::*;
+use uppy_up::GameState;
+use uppy_up::game::PlayerAction;
+use uppy_up::game::platform::Platform;
+use uppy_up::headless::Sim;
+use common::climb_until;
+use uppy_up::rules::{Climb, ClimbConfig, Direction, Location, Step, Tower};
+
+const SEED: u64 = 8;
+
+fn branching(lives: u32) -> ClimbConfig {
+    ClimbConfig { lives, decoys: true, branch_chance: 0.5, branch_depth: 3, ..ClimbConfig::default() }
+}
+
+#[test]
+fn branches_leave_the_path_alone() {
+    let mut plain = Tower::new(SEED);
+    let mut branched = Tower::new(SEED);
+    branched.set_branches(0.5, 3);
+    let mut below = Location::default();
+    let mut seen = 0;
+    for _ in 0..300 {
+        let platform = plain.grow();
+        assert_eq!(branched.grow(), platform);
+        let branch = branched.branches().last().unwrap();
+        let mut loc = below.clone();
+        let away = if platform.x > below.x {Direction::Left} else {Direction::Right};
+        for cloud in branch {
+            loc = loc.step(away);
+            assert_eq!(cloud, &loc);
+            assert!(cloud.x.abs() <= uppy_up::WINDOW_X / 2. - 50.);
+        }
+        assert!(branch.len() <= 3);
+        seen += branch.len();
+        below = platform;
+    }
+    assert!(seen > 0);
+    assert!(plain.branches().iter().all(Vec::is_empty));
+
+    // no branch cloud lands on a path cloud or another branch
+    let mut clouds: Vec<&Location> = branched.platforms().iter().collect();
+    clouds.extend(branched.branches().iter().flatten());
+    for (i, a) in clouds.iter().enumerate() {
+        for b in &clouds[i + 1..] {
+            assert!(a != b);
+        }
+    }
+}
+
+#[test]
+fn depth_and_chance_are_configurable() {
+    let grown = |chance: f32, depth: usize| {
+        let mut tower = Tower::new(SEED);
+        tower.set_branches(chance, depth);
+        for _ in 0..300 {
+            tower.grow();
+        }
+        tower.branches().to_vec()
+    };
+    assert!(grown(0.6, 1).iter().all(|branch| branch.len() <= 1));
+    assert!(grown(0.6, 4).iter().any(|branch| branch.len() > 2));
+    let count = |branches: Vec<Vec<Location>>| branches.iter().filter(|branch| !branch.is_empty()).count();
+    assert!(count(grown(0.6, 2)) > count(grown(0.1, 2)));
+    assert_eq!(count(grown(0., 2)), 0);
+}
+
+#[test]
+fn walking_a_branch_to_its_end() {
+    let mut climb = Climb::with_config(SEED, branching(1));
+    climb_until(&mut climb, |climb| !climb.tower().branches()[0].is_empty());
+    let score = climb.score();
+    let branch = climb.tower().branches()[0].clone();
+    let away = climb.tower().next_direction().unwrap().opposite();
+    for (i, cloud) in branch.iter().enumerate() {
+        let step = climb.step(away);
+        assert_eq!(step, Step::Branched { location: cloud.clone(), dead_end: i + 1 == branch.len() });
+        assert_eq!(climb.player(), cloud);
+        assert_eq!(climb.score(), score);
+    }
+    assert_eq!(climb.crumble(), branch);
+    assert!(climb.tower().branches()[0].is_empty());
+    assert!(!climb.on_branch());
+}
+
+#[test]
+fn turning_back_on_a_branch_is_a_wrong_step() {
+    let mut climb = Climb::with_config(SEED, branching(1));
+    climb_until(&mut climb, |climb| !climb.tower().branches()[0].is_empty());
+    let dir = climb.tower().next_direction().unwrap();
+    assert!(matches!(climb.step(dir.opposite()), Step::Branched { .. }));
+    assert_eq!(climb.step(dir), Step::WrongDirection);
+}
+
+#[test]
+fn a_dead_end_crumbles_and_ends_the_run() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(branching(1));
+    while sim.game().climb.tower().branches()[0].is_empty() {
+        sim.press_correct();
+    }
+    let score = sim.score();
+    let branch = sim.game().climb.tower().branches()[0].clone();
+    for _ in &branch {
+        sim.press_wrong();
+    }
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+    assert!(sim.game().platforms.placed().all(|(placed, _)| !branch.contains(placed)));
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.score(), score);
+}
+
+#[test]
+fn a_dead_end_costs_a_life_with_one_to_spare() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(branching(2));
+    while sim.game().climb.tower().branches()[0].is_empty() {
+        sim.press_correct();
+    }
+    let branch = sim.game().climb.tower().branches()[0].clone();
+    for _ in &branch {
+        sim.press_wrong();
+    }
+    sim.settle();
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+    assert_eq!(sim.game().climb.lives(), 1);
+    assert_eq!(sim.score(), 0);
+    // the crumbled branch doesn't come back
+    assert!(sim.game().climb.tower().branches().iter().flatten().all(|cloud| !branch.contains(cloud)));
+}
+
+#[test]
+fn decoys_are_tinted() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(branching(1));
+    sim.step();
+    let decoys: Vec<Location> = sim.game().climb.tower().branches().iter().flatten().cloned().collect();
+    assert!(!decoys.is_empty());
+
+    let world = sim.app.world_mut();
+    let mut tinted = 0;
+    for (platform, sprite, transform) in world.query::<(&Platform, &Sprite, &Transform)>().iter(world) {
+        let loc = Location { x: transform.translation.x, y: transform.translation.y };
+        assert_eq!(platform.decoy, decoys.contains(&loc));
+        assert_eq!(platform.decoy, sprite.color != Color::WHITE);
+        tinted += platform.decoy as usize;
+    }
+    assert_eq!(tinted, decoys.len());
+}
@@ -0,0 +1,226 @@
+mod common;
+
+use bevy::prelude::*;
+use uppy_up::GameState;
+use uppy_up::assets::GameAssets;
+use uppy_up::game::GameMode;
+use uppy_up::game::coin::Coin;
+use uppy_up::game::pause::PauseButton;
+use uppy_up::game::replay::Playback;
+use uppy_up::headless::Sim;
+use common::climb_until;
+use uppy_up::menu::MenuButton;
+use uppy_up::rules::{Climb, ClimbConfig, Location, Step, Tower};
+use uppy_up::save::SaveData;
+use uppy_up::shop::{CloudSkin, Cosmetic, PlayerSkin, ShopButton, ShopScreen};
+
+const SEED: u64 = 21;
+
+fn coins(lives: u32) -> ClimbConfig {
+    ClimbConfig { lives, coin_chance: 1., ..ClimbConfig::default() }
+}
+
+#[test]
+fn detours_go_round_zigzags() {
+    let mut plain = Tower::new(SEED);
+    let mut tower = Tower::new(SEED);
+    tower.set_coins(1.);
+    tower.set_branches(0.3, 2);
+    for _ in 0..300 {
+        assert_eq!(plain.grow(), tower.grow());
+    }
+    let mut seen = 0;
+    for index in 0..tower.platforms().len() - 1 {
+        let Some(detour) = &tower.detours()[index] else {
+            continue;
+        };
+        seen += 1;
+        let path = tower.correct_path();
+        assert_eq!(path[index + 1], path[index].opposite());
+        assert_eq!(detour.location.y, tower.platforms()[index].y);
+        assert_eq!(detour.location.step(path[index]), tower.platforms()[index + 1]);
+        assert!(tower.branches()[index].is_empty());
+        assert!(detour.coin);
+    }
+    assert!(seen > 20);
+
+    // no detour lands on another cloud
+    let mut clouds: Vec<&Location> = tower.platforms().iter().collect();
+    clouds.extend(tower.branches().iter().flatten());
+    clouds.extend(tower.detours().iter().flatten().map(|detour| &detour.location));
+    for (i, a) in clouds.iter().enumerate() {
+        for b in &clouds[i + 1..] {
+            assert!(a != b);
+        }
+    }
+}
+
+#[test]
+fn no_coins_before_the_first_hour_glass_and_never_round_one() {
+    let mut climb = Climb::with_config(SEED, coins(1));
+    assert!(climb.tower().detours().iter().all(Option::is_none));
+    for _ in 0..5 {
+        let check_point = climb.check_point().clone();
+        while climb.check_point() == &check_point {
+            let index = climb.tower().platforms().iter().position(|platform| *platform == check_point).unwrap();
+            assert!(climb.tower().detours()[index].is_none());
+            let dir = climb.next_direction().unwrap();
+            climb.step(dir);
+        }
+    }
+}
+
+#[test]
+fn going_round_collects_the_coin_and_scores_both_platforms() {
+    let mut climb = Climb::with_config(SEED, coins(1));
+    climb_until(&mut climb, |climb| climb.tower().detours()[0].is_some());
+    let climbing = climb.score_breakdown().climbing;
+    let score = climb.score();
+    let detour = climb.tower().detours()[0].clone().unwrap();
+    let after = climb.tower().platforms()[1].clone();
+    let dir = climb.next_direction().unwrap();
+
+    assert_eq!(climb.step(dir.opposite()), Step::Detoured { location: detour.location.clone(), coin: true });
+    assert_eq!(climb.player(), &detour.location);
+    assert_eq!(climb.coins(), 1);
+    assert_eq!(climb.score(), score);
+    assert_eq!(climb.next_direction(), Some(dir));
+
+    assert!(matches!(climb.step(dir), Step::Climbed { .. }));
+    assert_eq!(climb.player(), &after);
+    assert_eq!(climb.score_breakdown().climbing, climbing + 2);
+    assert!(!climb.on_detour());
+}
+
+#[test]
+fn a_coin_is_only_collected_once() {
+    let mut climb = Climb::with_config(SEED, coins(2));
+    climb_until(&mut climb, |climb| climb.tower().detours()[0].is_some());
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir.opposite());
+    // off the detour the wrong way
+    assert_eq!(climb.step(dir.opposite()), Step::WrongDirection);
+    assert!(climb.lose_life());
+    assert!(!climb.on_detour());
+
+    climb_until(&mut climb, |climb| climb.tower().detours()[0].is_some());
+    let dir = climb.next_direction().unwrap();
+    assert!(matches!(climb.step(dir.opposite()), Step::Detoured { coin: false, .. }));
+    assert_eq!(climb.coins(), 1);
+}
+
+#[test]
+fn coins_are_banked_at_the_end_of_a_run_but_not_a_replay() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(coins(1));
+    while sim.game().climb.tower().detours()[0].is_none() {
+        sim.press_correct();
+    }
+    sim.step();
+    let detour = sim.game().climb.tower().detours()[0].clone().unwrap();
+    let (_, entity) = sim.game().platforms.placed().find(|(placed, _)| *placed == detour.location).cloned().unwrap();
+    let coins_over = |sim: &mut Sim| {
+        let world = sim.app.world_mut();
+        world.get::<Children>(entity).map_or(0, |children| {
+            children.iter().filter(|child| world.get::<Coin>(**child).is_some()).count()
+        })
+    };
+    assert_eq!(coins_over(&mut sim), 1);
+
+    // the wrong way from the path is the way round
+    sim.press_wrong();
+    sim.step();
+    assert_eq!(coins_over(&mut sim), 0);
+    assert_eq!(sim.game().climb.coins(), 1);
+    sim.press_correct();
+    sim.press_wrong();
+    sim.finish_fall();
+    assert_eq!(sim.resource::<SaveData>().coins, 1);
+
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.config, coins(1));
+    assert_eq!(recorded.to_string().parse(), Ok(recorded.clone()));
+    let score = sim.score();
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.run_for(40.);
+    sim.finish_fall();
+    assert_eq!(sim.score(), score);
+    assert_eq!(sim.resource::<SaveData>().coins, 1);
+}
+
+// climbs to the first coin and goes round for it
+fn collect_first_coin(sim: &mut Sim) {
+    while sim.game().climb.tower().detours()[0].is_none() {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    assert_eq!(sim.game().climb.coins(), 1);
+}
+
+#[test]
+fn coins_are_banked_when_a_run_is_given_up() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(coins(1));
+    collect_first_coin(&mut sim);
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Restart);
+    assert_eq!(sim.resource::<SaveData>().coins, 1);
+    assert_eq!(sim.game().climb.coins(), 0);
+
+    collect_first_coin(&mut sim);
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Quit);
+    sim.step();
+    assert_eq!(sim.game_state(), GameState::Menu);
+    assert_eq!(sim.resource::<SaveData>().coins, 2);
+}
+
+#[test]
+fn buying_and_wearing() {
+    let mut save = SaveData::default();
+    let lime = Cosmetic::Player(PlayerSkin::Lime);
+    assert!(save.owns(Cosmetic::Player(PlayerSkin::BallGuy)));
+    assert!(save.wearing(Cosmetic::Cloud(CloudSkin::Classic)));
+    assert!(!save.buy(lime));
+    save.wear(lime);
+    assert!(!save.wearing(lime));
+
+    save.coins = lime.price() + 5;
+    assert!(save.buy(lime));
+    assert_eq!(save.coins, 5);
+    assert!(!save.buy(lime));
+    save.wear(lime);
+    assert!(save.wearing(lime));
+}
+
+#[test]
+fn the_shop_sells_skins_that_are_worn_next_run() {
+    let mut sim = Sim::new(SEED);
+    let storm = Cosmetic::Cloud(CloudSkin::Storm);
+    sim.resource_mut::<SaveData>().coins = storm.price();
+    sim.click(MenuButton::Shop);
+    assert_eq!(*sim.resource::<State<ShopScreen>>().get(), ShopScreen::Open);
+
+    // too dear
+    sim.click(ShopButton::Item(Cosmetic::Cloud(CloudSkin::Gold)));
+    assert_eq!(sim.resource::<SaveData>().coins, storm.price());
+
+    sim.click(ShopButton::Item(storm));
+    let save = sim.resource::<SaveData>();
+    assert_eq!(save.coins, 0);
+    assert!(save.owns(storm) && save.wearing(storm));
+    let assets = sim.resource::<GameAssets>();
+    assert_eq!(assets.cloud, assets.cloud_skins[&CloudSkin::Storm]);
+
+    sim.click(ShopButton::Back);
+    assert_eq!(*sim.resource::<State<ShopScreen>>().get(), ShopScreen::Closed);
+    sim.start(GameMode::Classic);
+    let storm_cloud = sim.resource::<GameAssets>().cloud.clone();
+    let world = sim.app.world_mut();
+    let plain = world.query::<(&uppy_up::game::platform::Platform, &Handle<Image>)>()
+        .iter(world)
+        .filter(|(platform, texture)| platform.kind == Default::default() && **texture == storm_cloud)
+        .count();
+    assert!(plain > 0);
+}
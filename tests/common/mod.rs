@@ -0,0 +1,9 @@
+use uppy_up::rules::{Climb, Step};
+
+// climbs the right way until the climb has got where it's wanted, for tests on the rules alone
+pub fn climb_until(climb: &mut Climb, done: impl Fn(&Climb) -> bool) {
+    while !done(climb) {
+        let dir = climb.next_direction().unwrap();
+        assert!(matches!(climb.step(dir), Step::Climbed { .. }), "a correct step should climb");
+    }
+}
@@ -0,0 +1,146 @@
+use uppy_up::GameState;
+use uppy_up::game::GameMode;
+use uppy_up::game::curve::Curve;
+use uppy_up::game::replay::{Playback, Replay};
+use uppy_up::headless::Sim;
+use uppy_up::rules::{Climb, ClimbConfig, CurveRow, DifficultyCurve, Y_INC};
+
+fn curve() -> DifficultyCurve {
+    DifficultyCurve(vec![
+        CurveRow::default(),
+        CurveRow { score: 10, time_scale: 0.5, extra_spacing: 5, decoys: true, hazards: false },
+    ])
+}
+
+fn config() -> ClimbConfig {
+    ClimbConfig {
+        check_point_time: 10.,
+        check_point_every: 10,
+        lookahead: 10,
+        // a point a platform, so rows come in at known heights
+        combo_window: 0.,
+        time_bonus: 0,
+        ..ClimbConfig::default()
+    }
+}
+
+fn climb_to_check_point(climb: &mut Climb) {
+    let reached = climb.check_points_reached();
+    while climb.check_points_reached() == reached {
+        let dir = climb.tower().next_direction().unwrap();
+        climb.step(dir);
+    }
+}
+
+#[test]
+fn rows_take_over_at_their_score() {
+    let curve = curve();
+    assert_eq!(curve.at(9), CurveRow::default());
+    assert_eq!(curve.at(10).time_scale, 0.5);
+    assert_eq!(curve.at(1000).extra_spacing, 5);
+    assert_eq!(DifficultyCurve::default().at(50), CurveRow::default());
+}
+
+#[test]
+fn hour_glasses_get_shorter_and_further_apart() {
+    let mut climb = Climb::with_curve(6, config(), curve());
+    climb_to_check_point(&mut climb);
+    assert_eq!(climb.score(), 10);
+
+    // 15 platforms up now, past the lookahead
+    assert_eq!(climb.check_point().y, climb.player().y + Y_INC * 15.);
+    assert_eq!(climb.tower().platforms().len(), 15);
+    assert!(!climb.tick(4.9));
+    assert!(climb.tick(0.2));
+}
+
+#[test]
+fn hazards_come_in_at_their_threshold() {
+    let mut climb = Climb::with_curve(6, config(), curve());
+    assert!(climb.tower().branches().iter().all(Vec::is_empty));
+    for _ in 0..3 {
+        climb_to_check_point(&mut climb);
+    }
+    assert!(climb.tower().branches().iter().any(|branch| !branch.is_empty()));
+}
+
+#[test]
+fn shipped_table_starts_at_zero() {
+    let shipped = Curve::default().0;
+    assert_eq!(shipped.0.first().map(|row| row.score), Some(0));
+    assert_eq!(shipped.at(0), CurveRow::default());
+    assert_eq!(shipped.check(), Ok(()));
+}
+
+#[test]
+fn tables_that_stop_the_clock_or_go_backwards_are_rejected() {
+    assert_eq!(curve().check(), Ok(()));
+    assert_eq!(DifficultyCurve::default().check(), Ok(()));
+
+    for time_scale in [0., -0.5, f32::NAN] {
+        let mut stopped = curve();
+        stopped.0[1].time_scale = time_scale;
+        assert!(stopped.check().is_err());
+    }
+
+    let mut backwards = curve();
+    backwards.0.reverse();
+    assert!(backwards.check().is_err());
+    let mut repeated = curve();
+    repeated.0[1].score = 0;
+    assert!(repeated.check().is_err());
+
+    let table = r#"[{ "score": 0 }, { "score": 10, "time_scale": 0 }]"#;
+    let parsed: DifficultyCurve = serde_json::from_str(table).unwrap();
+    assert!(parsed.check().is_err());
+}
+
+#[test]
+fn replays_with_a_broken_curve_are_rejected() {
+    let mut replay = Replay { seed: 6, curve: curve(), ..Replay::default() };
+    assert!(replay.to_string().parse::<Replay>().is_ok());
+    replay.curve.0[1].time_scale = 0.;
+    assert!(replay.to_string().parse::<Replay>().is_err());
+}
+
+#[test]
+fn replays_keep_the_curve_they_were_played_on() {
+    let mut sim = Sim::new(6);
+    sim.resource_mut::<Curve>().0 = curve();
+    sim.start(GameMode::Classic);
+    for _ in 0..40 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.curve, curve());
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+
+    sim.resource_mut::<Curve>().0 = DifficultyCurve::default();
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    // forty jumps take longer to play back than finish_fall waits
+    sim.run_for(6.);
+    sim.finish_fall();
+    assert_eq!(sim.score(), score);
+    assert_eq!(sim.game().climb.curve(), &curve());
+}
+
+#[test]
+fn spaced_out_platforms_get_clouds() {
+    let mut sim = Sim::new(6);
+    sim.resource_mut::<Curve>().0 = DifficultyCurve(vec![
+        CurveRow { score: 30, extra_spacing: 20, ..CurveRow::default() },
+    ]);
+    sim.start(GameMode::Classic);
+    for _ in 0..31 {
+        sim.press_correct();
+    }
+    let game = sim.game();
+    assert_eq!(game.climb.tower().platforms().len(), 50);
+    for platform in game.climb.tower().platforms() {
+        assert!(game.platforms.placed().any(|(placed, _)| placed == platform));
+    }
+}
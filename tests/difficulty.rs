@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use uppy_up::GameState;
+use uppy_up::game::GameMode;
+use uppy_up::game::daily::UtcDate;
+use uppy_up::game::score::HighScoreEntity;
+use uppy_up::game::replay::{Playback, Replay};
+use uppy_up::headless::Sim;
+use uppy_up::menu::MenuButton;
+use uppy_up::rules::{Climb, ClimbConfig, ClimbOption, Difficulty};
+use uppy_up::save::{SaveData, Storage};
+use uppy_up::settings::Settings;
+
+#[test]
+fn presets_get_harder_and_keep_the_lives() {
+    let custom = ClimbConfig { lives: 3, ..ClimbConfig::default() };
+    let easy = Difficulty::Easy.config(&custom);
+    let normal = Difficulty::Normal.config(&custom);
+    let hard = Difficulty::Hard.config(&custom);
+
+    assert!(easy.check_point_time > normal.check_point_time);
+    assert!(normal.check_point_time > hard.check_point_time);
+    assert!(easy.lookahead > hard.lookahead);
+    assert!(!easy.decoys && hard.decoys);
+    for config in [easy, normal, hard] {
+        assert_eq!(config.lives, 3);
+    }
+    assert_eq!(Difficulty::Custom.config(&custom), custom);
+}
+
+#[test]
+fn custom_options_stay_playable() {
+    let mut config = ClimbConfig::default();
+    for _ in 0..10 {
+        config.step(ClimbOption::Lookahead, -1);
+    }
+    assert_eq!(config.lookahead, 10);
+    assert_eq!(config.check_point_every, 10);
+
+    config.step(ClimbOption::CheckPointEvery, 1);
+    assert_eq!(config.check_point_every, 10);
+
+    for _ in 0..100 {
+        config.step(ClimbOption::CheckPointTime, -1);
+    }
+    assert_eq!(config.check_point_time, 5.);
+}
+
+#[test]
+fn config_shapes_the_climb() {
+    let config = ClimbConfig {
+        check_point_time: 8.,
+        check_point_every: 10,
+        lookahead: 15,
+        ..ClimbConfig::default()
+    };
+    let mut climb = Climb::with_config(3, config);
+    assert_eq!(climb.tower().platforms().len(), 15);
+    assert_eq!(climb.check_point(), &climb.tower().platforms()[9]);
+
+    for _ in 0..10 {
+        let dir = climb.tower().next_direction().unwrap();
+        climb.step(dir);
+    }
+    assert_eq!(climb.check_points_reached(), 1);
+    assert!(!climb.tick(7.9));
+    assert!(climb.tick(0.2));
+}
+
+#[test]
+fn difficulty_is_picked_on_the_menu() {
+    let mut sim = Sim::new(4);
+    sim.click(MenuButton::Difficulty);
+    assert_eq!(sim.resource::<Settings>().difficulty, Difficulty::Hard);
+
+    sim.start(GameMode::Classic);
+    assert_eq!(sim.game().climb.tower().platforms().len(), Difficulty::Hard.config(&ClimbConfig::default()).lookahead);
+    assert!(sim.game().climb.config().decoys);
+}
+
+#[test]
+fn each_difficulty_keeps_its_own_high_score() {
+    let mut sim = Sim::new(4);
+    sim.resource_mut::<Settings>().difficulty = Difficulty::Easy;
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    sim.press_correct();
+    sim.press_wrong();
+    sim.finish_fall();
+
+    let save = sim.resource::<SaveData>();
+    assert_eq!(save.high_score(Difficulty::Easy), 2);
+    assert_eq!(save.high_score(Difficulty::Normal), 0);
+
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.config, Difficulty::Easy.config(&ClimbConfig::default()));
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+}
+
+fn high_score_shown(sim: &mut Sim) -> Option<String> {
+    let world = sim.app.world_mut();
+    world.query_filtered::<&Text, With<HighScoreEntity>>()
+        .iter(world)
+        .next()
+        .map(|text| text.sections[0].value.clone())
+}
+
+#[test]
+fn daily_runs_and_their_replays_show_the_normal_high_score() {
+    let mut sim = Sim::new(4);
+    {
+        let mut save = sim.resource_mut::<SaveData>();
+        save.record_high_score(Difficulty::Normal, 5);
+        save.record_high_score(Difficulty::Hard, 9);
+    }
+    sim.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    sim.start(GameMode::Daily(UtcDate::today()));
+    assert_eq!(sim.game().difficulty, Difficulty::Normal);
+    assert_eq!(high_score_shown(&mut sim), Some(String::from("High Score: 5")));
+    sim.press_wrong();
+    sim.finish_fall();
+
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    assert_eq!(sim.game().difficulty, Difficulty::Normal);
+    assert_eq!(high_score_shown(&mut sim), Some(String::from("High Score: 5")));
+}
+
+#[test]
+fn replays_show_the_high_score_of_the_difficulty_they_were_played_on() {
+    let mut sim = Sim::new(4);
+    sim.resource_mut::<SaveData>().record_high_score(Difficulty::Hard, 9);
+    sim.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    sim.start(GameMode::Classic);
+    sim.press_wrong();
+    sim.finish_fall();
+
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.difficulty, Difficulty::Hard);
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+
+    // whatever the menu has been switched to since
+    sim.set_state(GameState::Menu);
+    sim.resource_mut::<Settings>().difficulty = Difficulty::Easy;
+    sim.start(GameMode::Replay);
+    assert_eq!(sim.game().difficulty, Difficulty::Hard);
+    assert_eq!(high_score_shown(&mut sim), Some(String::from("High Score: 9")));
+}
+
+#[test]
+fn replays_from_before_the_difficulty_was_recorded_go_by_their_options() {
+    let hard = Difficulty::Hard.config(&ClimbConfig::default());
+    let mut replay = Replay { seed: 4, difficulty: Difficulty::Hard, config: hard, ..Replay::default() };
+    let old = replay.to_string()
+        .replace("uppy-up replay 9", "uppy-up replay 8")
+        .replace("difficulty Hard\n", "");
+    assert_eq!(old.parse::<Replay>().unwrap().difficulty, Difficulty::Hard);
+
+    replay.config.lookahead += 1;
+    let old = replay.to_string()
+        .replace("uppy-up replay 9", "uppy-up replay 8")
+        .replace("difficulty Hard\n", "");
+    assert_eq!(old.parse::<Replay>().unwrap().difficulty, Difficulty::Custom);
+}
+
+#[test]
+fn old_saves_keep_their_high_score_as_normal() {
+    let mut storage = Storage::in_memory();
+    storage.write("save.json", r#"{"version":1,"high_score":17}"#).unwrap();
+    let save = SaveData::load(&storage);
+    assert_eq!(save.high_score(Difficulty::Normal), 17);
+    assert_eq!(save.high_score(Difficulty::Hard), 0);
+}
+
+#[test]
+fn decoy_clouds_are_drawn() {
+    let mut sim = Sim::new(8);
+    sim.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    sim.start(GameMode::Classic);
+    for _ in 0..10 {
+        sim.press_correct();
+    }
+    let game = sim.game();
+    for decoy in game.climb.tower().branches().iter().flatten() {
+        assert!(game.platforms.placed().any(|(placed, _)| placed == decoy));
+    }
+}
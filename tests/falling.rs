@@ -0,0 +1,70 @@
+use uppy_up::game::{CameraShake, Direction, GameMode, Motion, PlayerAction};
+use uppy_up::game::player::PLAYER_OFFSET;
+use uppy_up::headless::Sim;
+use uppy_up::rules::START_Y;
+use uppy_up::GameState;
+
+const GROUND_Y: f32 = START_Y + PLAYER_OFFSET;
+
+fn climbed(bounce: bool) -> Sim {
+    let mut sim = Sim::new(42);
+    sim.resource_mut::<Motion>().bounce = bounce;
+    sim.start(GameMode::Classic);
+    for _ in 0..8 {
+        sim.press_correct();
+    }
+    sim
+}
+
+// steps through the fall, returning each frame's drawn position
+fn fall(sim: &mut Sim) -> Vec<(f32, f32)> {
+    let mut path = Vec::new();
+    while sim.game_state() == GameState::Playing {
+        let location = sim.game().player_location();
+        path.push((location.x, location.y));
+        sim.step();
+    }
+    path
+}
+
+#[test]
+fn falls_accelerate_and_drift_the_wrong_way() {
+    let mut sim = climbed(false);
+    let wrong = sim.next_direction().opposite();
+    let start_x = sim.game().player_location().x;
+    sim.press(wrong);
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+
+    let path = fall(&mut sim);
+    let drops: Vec<f32> = path.windows(2).map(|pair| pair[0].1 - pair[1].1).take(10).collect();
+    assert!(drops.windows(2).all(|pair| pair[1] > pair[0]), "{drops:?}");
+
+    let (end_x, end_y) = *path.last().unwrap();
+    match wrong {
+        Direction::Left => assert!(end_x < start_x),
+        Direction::Right => assert!(end_x > start_x),
+    }
+    assert_eq!(end_y, GROUND_Y);
+}
+
+#[test]
+fn bouncing_leaves_the_ground_again() {
+    let mut sim = climbed(true);
+    sim.press_wrong();
+    let path = fall(&mut sim);
+    let first_landing = path.iter().position(|(_, y)| *y == GROUND_Y).unwrap();
+    assert!(path[first_landing..].iter().any(|(_, y)| *y > GROUND_Y));
+}
+
+#[test]
+fn landing_shakes_the_camera_before_game_over() {
+    let mut sim = climbed(false);
+    sim.press_wrong();
+    let mut shook = false;
+    while sim.game_state() == GameState::Playing {
+        shook |= sim.resource::<CameraShake>().offset() != bevy::math::Vec2::ZERO;
+        sim.step();
+    }
+    assert!(shook);
+    assert_eq!(sim.game_state(), GameState::GameOver);
+}
@@ -0,0 +1,208 @@
+use uppy_up::GameState;
+use uppy_up::game::{Direction, GameMode, PlayerAction};
+use uppy_up::game::replay::Playback;
+use uppy_up::headless::Sim;
+use uppy_up::rules::Difficulty;
+use uppy_up::save::{SaveData, Storage};
+
+const SEED: u64 = 42;
+const FRAME_TIME: f32 = 1. / 60.;
+
+fn playing() -> Sim {
+    let mut sim = Sim::new(SEED);
+    sim.start(GameMode::Classic);
+    sim
+}
+
+// climbs to the platform below the first hour glass and waits
+// until this many frames are left on the clock before pressing for it
+fn press_for_hour_glass(frames_left: u32) -> Sim {
+    let mut sim = playing();
+    for _ in 0..29 {
+        sim.press_correct();
+    }
+    while sim.game().climb.clock().remaining() > (frames_left as f32 + 0.1) * FRAME_TIME {
+        sim.step();
+    }
+    sim.press_correct();
+    sim
+}
+
+// plays the finished run back, long enough for the clock to run out if it did live
+fn replay(sim: &mut Sim) {
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.run_for(25.);
+    sim.finish_fall();
+}
+
+#[test]
+fn starts_with_a_tower_ahead() {
+    let sim = playing();
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+    assert_eq!(sim.score(), 0);
+    assert_eq!(sim.game().climb.tower().platforms().len(), 30);
+    assert_eq!(sim.game().climb.tower().correct_path().len(), 30);
+}
+
+#[test]
+fn correct_jumps_score_and_keep_the_lookahead() {
+    let mut sim = playing();
+    for jump in 1..=10 {
+        let next = sim.game().climb.tower().platforms()[0].clone();
+        sim.press_correct();
+        assert_eq!(sim.player_action(), PlayerAction::Rest);
+        // quick jumps score more on top, see tests/scoring.rs
+        assert_eq!(sim.game().climb.score_breakdown().climbing, jump);
+        assert_eq!(sim.score(), sim.game().climb.score());
+        assert_eq!(sim.game().climb.tower().platforms().len(), 30);
+        assert_eq!(sim.game().player_location().y - next.y, uppy_up::game::player::PLAYER_OFFSET);
+    }
+}
+
+#[test]
+fn wrong_direction_ends_the_run() {
+    let mut sim = playing();
+    sim.press_correct();
+    sim.press_correct();
+    sim.press_wrong();
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.score(), 2);
+    assert_eq!(sim.resource::<SaveData>().high_score(Difficulty::Normal), 2);
+}
+
+#[test]
+fn a_save_from_a_newer_version_is_never_written_over() {
+    let newer = r#"{"version":99,"coins":500}"#;
+    let mut sim = Sim::new(SEED);
+    sim.resource_mut::<Storage>().write("save.json", newer).unwrap();
+    let save = SaveData::load(sim.resource::<Storage>());
+    assert!(save.read_only());
+    assert_eq!(save.coins, 0);
+    *sim.resource_mut::<SaveData>() = save;
+
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    sim.press_wrong();
+    sim.finish_fall();
+    assert_eq!(sim.resource::<SaveData>().high_score(Difficulty::Normal), 1);
+    assert_eq!(sim.resource::<Storage>().read("save.json").as_deref(), Some(newer));
+}
+
+#[test]
+fn running_out_of_time_ends_the_run() {
+    let mut sim = playing();
+    sim.press_correct();
+    sim.run_for(19.);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+    assert_eq!(sim.game_state(), GameState::Playing);
+    sim.run_for(1.5);
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.score(), 1);
+}
+
+#[test]
+fn reaching_a_check_point_resets_the_timer() {
+    let mut sim = playing();
+    // the climb itself takes a few seconds of jumping
+    for _ in 0..29 {
+        sim.press_correct();
+    }
+    sim.run_for(10.);
+    assert_eq!(sim.game().climb.check_points_reached(), 0);
+    sim.press_correct();
+    assert_eq!(sim.game().climb.check_points_reached(), 1);
+    sim.run_for(15.);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+    assert_eq!(sim.game().climb.score_breakdown().climbing, 30);
+}
+
+#[test]
+fn same_seed_builds_the_same_tower() {
+    let first = playing();
+    let second = playing();
+    assert_eq!(first.game().climb.tower().correct_path(), second.game().climb.tower().correct_path());
+    assert_eq!(first.game().climb.tower().platforms(), second.game().climb.tower().platforms());
+}
+
+#[test]
+fn replay_reproduces_the_run() {
+    let mut sim = playing();
+    for _ in 0..5 {
+        sim.run_for(0.3);
+        sim.press_correct();
+    }
+    sim.press(Direction::Left);
+    sim.press(Direction::Right);
+    sim.finish_fall();
+    let score = sim.score();
+
+    replay(&mut sim);
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.game().climb.check_points_reached(), 0);
+    assert_eq!(sim.score(), score);
+}
+
+#[test]
+fn replays_pass_an_hour_glass_with_little_time_left() {
+    let mut sim = press_for_hour_glass(6);
+    assert_eq!(sim.game().climb.check_points_reached(), 1);
+    sim.press_correct();
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+
+    replay(&mut sim);
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.game().climb.check_points_reached(), 1);
+    assert_eq!(sim.score(), score);
+}
+
+#[test]
+fn replays_run_out_of_time_where_the_run_did() {
+    // a frame too late, the clock runs out before the jump is taken
+    let mut sim = press_for_hour_glass(2);
+    sim.finish_fall();
+    assert_eq!(sim.game().climb.check_points_reached(), 0);
+    assert!(sim.game().climb.clock().expired());
+    let score = sim.score();
+
+    replay(&mut sim);
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.game().climb.check_points_reached(), 0);
+    assert!(sim.game().climb.clock().expired());
+    assert_eq!(sim.score(), score);
+}
+
+#[test]
+fn replay_files_round_trip() {
+    let mut sim = playing();
+    sim.press_correct();
+    sim.run_for(0.5);
+    sim.press_wrong();
+    sim.finish_fall();
+    let replay = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(replay.seed, SEED);
+    assert_eq!(replay.inputs.len(), 2);
+    assert_eq!(replay.to_string().parse(), Ok(replay));
+}
+
+#[test]
+fn replays_reach_an_hour_glass_with_a_frame_to_spare() {
+    // the press lands a frame later and the jump is taken the frame after that
+    let mut sim = press_for_hour_glass(3);
+    assert_eq!(sim.game().climb.check_points_reached(), 1);
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+
+    replay(&mut sim);
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.game().climb.check_points_reached(), 1);
+    assert_eq!(sim.score(), score);
+}
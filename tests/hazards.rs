@@ -0,0 +1,255 @@
+mod common;
+
+use uppy_up::GameState;
+use uppy_up::game::{GameMode, PlayerAction};
+use uppy_up::game::platform::Platform;
+use uppy_up::game::replay::Playback;
+use uppy_up::headless::Sim;
+use common::climb_until;
+use uppy_up::rules::{
+    Climb, ClimbConfig, Direction, HazardWeights, Location, PlatformKind, Step, Tower,
+    CRUMBLE_DELAY, DRIFT_PERIOD, SPIKE_TIME, X_INC, Y_INC,
+};
+
+const SEED: u64 = 12;
+const FRAME_TIME: f32 = 1. / 60.;
+
+fn hazards(lives: u32) -> ClimbConfig {
+    ClimbConfig { lives, hazards: true, ..ClimbConfig::default() }
+}
+
+fn sim_to(sim: &mut Sim, kind: PlatformKind) {
+    while sim.game().climb.tower().kinds()[0] != kind {
+        sim.press_correct();
+    }
+}
+
+// jumps onto the next drifting cloud on the last frame before it crosses over to its other side,
+// a replay that's a frame late lands on the other side and goes the wrong way from there
+fn press_at_the_crossover(sim: &mut Sim) {
+    sim_to(sim, PlatformKind::Drifting);
+    let drift = sim.game().climb.tower().drift(0).unwrap();
+    // the jump is taken two frames after the press
+    let landing = |sim: &Sim, frames: f32| drift.landing(sim.game().climb.time() + frames * FRAME_TIME);
+    while landing(sim, 2.) == landing(sim, 3.) {
+        sim.step();
+    }
+    let target = landing(sim, 2.);
+    let toward = if target.x < sim.game().climb.player().x {Direction::Left} else {Direction::Right};
+    sim.press(toward);
+    assert_eq!(sim.game().climb.player(), &target);
+}
+
+#[test]
+fn weights_pick_the_kind() {
+    let only_spikes = HazardWeights { normal: 0, crumbling: 0, drifting: 0, spiked: 1 };
+    assert_eq!(only_spikes.pick(0.), PlatformKind::Spiked);
+    assert_eq!(only_spikes.pick(0.99), PlatformKind::Spiked);
+    let none = HazardWeights { normal: 0, crumbling: 0, drifting: 0, spiked: 0 };
+    assert_eq!(none.pick(0.5), PlatformKind::Normal);
+    let even = HazardWeights { normal: 1, crumbling: 1, drifting: 1, spiked: 1 };
+    assert_eq!(even.pick(0.1), PlatformKind::Normal);
+    assert_eq!(even.pick(0.3), PlatformKind::Crumbling);
+    assert_eq!(even.pick(0.6), PlatformKind::Drifting);
+    assert_eq!(even.pick(0.9), PlatformKind::Spiked);
+}
+
+#[test]
+fn hazards_only_when_turned_on() {
+    let mut plain = Tower::new(SEED);
+    let mut mixed = Tower::new(SEED);
+    mixed.set_hazards(Some(HazardWeights::default()));
+    for _ in 0..300 {
+        plain.grow();
+        mixed.grow();
+    }
+    assert!(plain.kinds().iter().all(|kind| *kind == PlatformKind::Normal));
+    for kind in PlatformKind::ALL {
+        assert!(mixed.kinds().contains(&kind), "no {kind:?} platforms");
+    }
+}
+
+#[test]
+fn drifting_platforms_fit_and_the_next_step_reaches_both_sides() {
+    let mut tower = Tower::new(SEED);
+    tower.set_hazards(Some(HazardWeights { drifting: 6, ..HazardWeights::default() }));
+    for _ in 0..300 {
+        tower.grow();
+    }
+    let mut below = Location::default();
+    for index in 0..tower.platforms().len() - 1 {
+        let platform = &tower.platforms()[index];
+        assert_eq!(platform.y, below.y + Y_INC);
+        assert_eq!((platform.x - below.x).abs(), X_INC);
+        if let Some(drift) = tower.drift(index) {
+            assert!(drift.away.x.abs() <= uppy_up::WINDOW_X / 2. - 50.);
+            assert_eq!(drift.away.x + drift.home.x, 2. * below.x);
+            assert_eq!(tower.platforms()[index + 1].x, below.x);
+            assert!(tower.branches()[index].is_empty() && tower.branches()[index + 1].is_empty());
+        }
+        below = platform.clone();
+    }
+}
+
+#[test]
+fn hour_glasses_sit_on_plain_clouds() {
+    let mut climb = Climb::with_config(SEED, hazards(1));
+    for _ in 0..5 {
+        let check_point = climb.check_point().clone();
+        let index = climb.tower().platforms().iter().position(|platform| *platform == check_point).unwrap();
+        assert_eq!(climb.tower().kinds()[index], PlatformKind::Normal);
+        while climb.check_point() == &check_point {
+            let dir = climb.next_direction().unwrap();
+            climb.step(dir);
+        }
+    }
+}
+
+#[test]
+fn spikes_cost_time() {
+    let mut climb = Climb::with_config(SEED, hazards(1));
+    climb_until(&mut climb, |climb| climb.tower().kinds()[0] == PlatformKind::Spiked);
+    let elapsed = climb.clock().elapsed();
+    let dir = climb.next_direction().unwrap();
+    assert!(matches!(climb.step(dir), Step::Climbed { kind: PlatformKind::Spiked, .. }));
+    assert_eq!(climb.clock().elapsed(), elapsed + SPIKE_TIME);
+}
+
+#[test]
+fn crumbling_platforms_give_way_unless_the_player_moves_on() {
+    let mut climb = Climb::with_config(SEED, hazards(1));
+    climb_until(&mut climb, |climb| climb.tower().kinds()[0] == PlatformKind::Crumbling);
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir);
+    climb.tick(CRUMBLE_DELAY - 0.1);
+    assert!(!climb.collapsed());
+    climb.tick(0.2);
+    assert!(climb.collapsed());
+    assert_eq!(&climb.collapse(), climb.player());
+    assert!(!climb.collapsed());
+
+    let mut climb = Climb::with_config(SEED, hazards(1));
+    climb_until(&mut climb, |climb| climb.tower().kinds()[0] == PlatformKind::Crumbling);
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir);
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir);
+    climb.tick(1.);
+    // moving on starts the next one's count if it's crumbling too
+    let dir = climb.next_direction().unwrap();
+    let Step::Climbed { kind, .. } = climb.step(dir) else {
+        panic!("should climb");
+    };
+    let fresh = (kind == PlatformKind::Crumbling).then_some(CRUMBLE_DELAY);
+    assert_eq!(climb.crumbling(), fresh);
+}
+
+#[test]
+fn drifting_changes_the_way_to_it() {
+    let mut climb = Climb::with_config(SEED, hazards(1));
+    climb_until(&mut climb, |climb| climb.tower().kinds()[0] == PlatformKind::Drifting);
+    let drift = climb.tower().drift(0).unwrap();
+    let mut seen = Vec::new();
+    for _ in 0..40 {
+        let dir = climb.next_direction().unwrap();
+        let toward = if drift.landing(climb.time()).x < climb.player().x {Direction::Left} else {Direction::Right};
+        assert_eq!(dir, toward);
+        if !seen.contains(&dir) {
+            seen.push(dir);
+        }
+        climb.tick(DRIFT_PERIOD / 20.);
+    }
+    assert_eq!(seen.len(), 2);
+
+    // whichever side it's landed on, the path carries on from there
+    let landing = drift.landing(climb.time());
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir);
+    assert_eq!(climb.player(), &landing);
+    let dir = climb.next_direction().unwrap();
+    assert_eq!(climb.player().step(dir), climb.tower().platforms()[0]);
+    assert!(matches!(climb.step(dir), Step::Climbed { .. }));
+}
+
+#[test]
+fn a_collapse_ends_the_run() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(hazards(1));
+    sim_to(&mut sim, PlatformKind::Crumbling);
+    sim.press_correct();
+    let platform = sim.game().climb.player().clone();
+    sim.run_for(CRUMBLE_DELAY + 0.1);
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+    assert!(sim.game().platforms.placed().all(|(placed, _)| *placed != platform));
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+}
+
+#[test]
+fn a_collapse_with_a_spare_life_puts_the_cloud_back() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(hazards(2));
+    sim_to(&mut sim, PlatformKind::Crumbling);
+    sim.press_correct();
+    let platform = sim.game().climb.player().clone();
+    sim.run_for(CRUMBLE_DELAY + 0.1);
+    sim.settle();
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(sim.game().climb.lives(), 1);
+    assert!(sim.game().platforms.placed().any(|(placed, _)| *placed == platform));
+}
+
+#[test]
+fn clouds_are_drawn_as_their_kind() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(hazards(1));
+    sim.step();
+    let tower = sim.game().climb.tower();
+    let kinds: Vec<(Location, PlatformKind)> = tower.platforms().iter().cloned().zip(tower.kinds().iter().copied()).collect();
+    let placed: Vec<(Location, bevy::prelude::Entity)> = sim.game().platforms.placed().cloned().collect();
+    let world = sim.app.world_mut();
+    for (loc, kind) in kinds {
+        let (_, entity) = placed.iter().find(|(placed, _)| *placed == loc).unwrap();
+        let platform = world.get::<Platform>(*entity).unwrap();
+        assert_eq!(platform.kind, kind);
+        assert_eq!(platform.drift.is_some(), kind == PlatformKind::Drifting);
+    }
+}
+
+#[test]
+fn replays_keep_the_hazards() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(hazards(1));
+    for _ in 0..40 {
+        sim.run_for(0.2);
+        sim.press_correct();
+    }
+    press_at_the_crossover(&mut sim);
+    let landed = sim.game().climb.player().clone();
+    for _ in 0..3 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+    let check_points = sim.game().climb.check_points_reached();
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.config, hazards(1));
+    assert_eq!(recorded.to_string().parse(), Ok(recorded.clone()));
+
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    // it gets onto the drifting cloud on the same side, or it's gone wrong
+    for _ in 0..1200 {
+        if sim.game().climb.player().y >= landed.y {
+            break;
+        }
+        sim.step();
+    }
+    assert_eq!(sim.game().climb.player(), &landed);
+    sim.run_for(5.);
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.game().climb.check_points_reached(), check_points);
+    assert_eq!(sim.score(), score);
+}
@@ -0,0 +1,120 @@
+use bevy::input::gamepad::{Gamepad, GamepadAxisChangedEvent, GamepadAxisType, GamepadButtonChangedEvent, GamepadButtonType, GamepadEvent};
+use bevy::prelude::KeyCode;
+use uppy_up::game::{Direction, GameMode, PlayerAction};
+use uppy_up::headless::Sim;
+use uppy_up::save::{load_json, Storage};
+use uppy_up::settings::{KeyBindings, Settings, SETTINGS_NAME};
+
+fn playing() -> Sim {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    sim
+}
+
+fn send(sim: &mut Sim, event: GamepadEvent) {
+    sim.app.world_mut().send_event(event);
+    sim.step();
+    sim.settle();
+}
+
+fn button(button_type: GamepadButtonType, value: f32) -> GamepadEvent {
+    GamepadButtonChangedEvent::new(Gamepad::new(0), button_type, value).into()
+}
+
+fn stick(value: f32) -> GamepadEvent {
+    GamepadAxisChangedEvent::new(Gamepad::new(0), GamepadAxisType::LeftStickX, value).into()
+}
+
+fn dpad(direction: Direction) -> GamepadButtonType {
+    match direction {
+        Direction::Left => GamepadButtonType::DPadLeft,
+        Direction::Right => GamepadButtonType::DPadRight,
+    }
+}
+
+#[test]
+fn dpad_and_shoulders_jump() {
+    let mut sim = playing();
+    for jump in 1..=4 {
+        let direction = sim.next_direction();
+        let pressed = if jump % 2 == 0 {
+            dpad(direction)
+        }
+        else {
+            match direction {
+                Direction::Left => GamepadButtonType::LeftTrigger,
+                Direction::Right => GamepadButtonType::RightTrigger,
+            }
+        };
+        send(&mut sim, button(pressed, 1.));
+        send(&mut sim, button(pressed, 0.));
+        assert_eq!(sim.score(), jump);
+    }
+}
+
+#[test]
+fn stick_has_to_return_before_jumping_again() {
+    let mut sim = playing();
+    let value = |direction| if direction == Direction::Left {-1.} else {1.};
+
+    let first = sim.next_direction();
+    send(&mut sim, stick(value(first)));
+    assert_eq!(sim.score(), 1);
+
+    // holding the stick over doesn't keep climbing
+    sim.run_for(0.5);
+    assert_eq!(sim.score(), 1);
+
+    send(&mut sim, stick(0.));
+    let second = sim.next_direction();
+    send(&mut sim, stick(value(second)));
+    assert_eq!(sim.score(), 2);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+}
+
+fn key(sim: &mut Sim, key_code: KeyCode) {
+    sim.tap_key(key_code);
+    sim.settle();
+}
+
+#[test]
+fn default_keys_jump() {
+    let mut sim = playing();
+    let keys = KeyBindings::default();
+    for jump in 1..=4 {
+        let direction = sim.next_direction();
+        key(&mut sim, keys.keys(direction)[jump % 2]);
+        assert_eq!(sim.score(), jump as i32);
+    }
+}
+
+#[test]
+fn rebound_keys_jump_and_old_ones_dont() {
+    let mut sim = playing();
+    sim.resource_mut::<Settings>().bindings.bind(Direction::Left, 0, KeyCode::KeyJ);
+    sim.resource_mut::<Settings>().bindings.bind(Direction::Right, 0, KeyCode::KeyL);
+    sim.step();
+
+    let direction = sim.next_direction();
+    let old = KeyBindings::default().keys(direction)[0];
+    key(&mut sim, old);
+    assert_eq!(sim.score(), 0);
+
+    let new = match direction {
+        Direction::Left => KeyCode::KeyJ,
+        Direction::Right => KeyCode::KeyL,
+    };
+    key(&mut sim, new);
+    assert_eq!(sim.score(), 1);
+
+    let saved: Settings = load_json(sim.resource::<Storage>(), SETTINGS_NAME);
+    assert_eq!(saved.bindings.keys(Direction::Left)[0], KeyCode::KeyJ);
+}
+
+#[test]
+fn binding_a_used_key_swaps() {
+    let mut keys = KeyBindings::default();
+    keys.bind(Direction::Left, 0, KeyCode::KeyD);
+    assert_eq!(keys.keys(Direction::Left), &[KeyCode::KeyD, KeyCode::ArrowLeft]);
+    assert_eq!(keys.keys(Direction::Right), &[KeyCode::KeyA, KeyCode::ArrowRight]);
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use uppy_up::audio::effects::Effect;
+use uppy_up::game::GameMode;
+use uppy_up::game::pause::{PauseButton, PlayState};
+use uppy_up::headless::Sim;
+use uppy_up::GameState;
+
+// sound effects despawn when they finish, which never happens headless
+fn entity_count(sim: &mut Sim) -> usize {
+    let world = sim.app.world_mut();
+    world.query_filtered::<Entity, Without<Effect>>().iter(world).count()
+}
+
+// on the menu with the music done fading
+fn settled_menu(sim: &mut Sim) -> usize {
+    sim.run_for(2.);
+    assert_eq!(sim.game_state(), GameState::Menu);
+    entity_count(sim)
+}
+
+fn play_to_game_over_and_back(sim: &mut Sim) -> usize {
+    sim.start(GameMode::Classic);
+    for _ in 0..10 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    sim.run_for(1.);
+    sim.set_state(GameState::Menu);
+    settled_menu(sim)
+}
+
+#[test]
+fn full_cycle_returns_to_baseline() {
+    let mut sim = Sim::new(42);
+    // the first finished run adds the REPLAY button to the menu
+    let baseline = play_to_game_over_and_back(&mut sim);
+
+    for _ in 0..2 {
+        assert_eq!(play_to_game_over_and_back(&mut sim), baseline);
+    }
+}
+
+#[test]
+fn quitting_from_pause_returns_to_baseline() {
+    let mut sim = Sim::new(42);
+    let baseline = settled_menu(&mut sim);
+
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    sim.app.world_mut().resource_mut::<NextState<PlayState>>().set(PlayState::Paused);
+    sim.step();
+    sim.click(PauseButton::Quit);
+    assert_eq!(settled_menu(&mut sim), baseline);
+}
+
+#[test]
+fn restarting_does_not_leak() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    sim.run_for(2.);
+    let fresh = entity_count(&mut sim);
+
+    for _ in 0..3 {
+        sim.app.world_mut().resource_mut::<NextState<PlayState>>().set(PlayState::Paused);
+        sim.step();
+        sim.click(PauseButton::Restart);
+        sim.settle();
+    }
+    assert_eq!(entity_count(&mut sim), fresh);
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use uppy_up::GameState;
+use uppy_up::game::{GameMode, PlayerAction};
+use uppy_up::game::player::standing_on;
+use uppy_up::game::replay::Playback;
+use uppy_up::headless::Sim;
+use uppy_up::rules::{ClimbConfig, Location, CHECK_POINT_TIME};
+use uppy_up::settings::Settings;
+
+const SEED: u64 = 21;
+
+fn playing_with_lives(lives: u32) -> Sim {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(ClimbConfig { lives, ..ClimbConfig::default() });
+    sim
+}
+
+#[test]
+fn wrong_step_costs_a_life_instead_of_the_run() {
+    let mut sim = playing_with_lives(3);
+    for _ in 0..5 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+    assert_eq!(sim.game().climb.lives(), 2);
+    assert_eq!(sim.score(), 0);
+    assert_eq!(sim.game().player_location(), &standing_on(&Location::default()));
+
+    // the same tower is still there to climb
+    sim.press_correct();
+    assert_eq!(sim.score(), 1);
+}
+
+#[test]
+fn game_over_only_when_the_lives_run_out() {
+    let mut sim = playing_with_lives(3);
+    sim.press_wrong();
+    sim.press_wrong();
+    assert_eq!(sim.game_state(), GameState::Playing);
+
+    sim.press_wrong();
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+    sim.finish_fall();
+    assert_eq!(sim.game().climb.lives(), 0);
+}
+
+#[test]
+fn running_out_of_time_costs_a_life() {
+    let mut sim = playing_with_lives(3);
+    sim.press_correct();
+    sim.run_for(CHECK_POINT_TIME + 0.5);
+    sim.settle();
+
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(sim.game().climb.lives(), 2);
+    assert!(!sim.game().climb.clock().expired());
+}
+
+#[test]
+fn one_life_is_the_classic_game() {
+    let mut sim = playing_with_lives(1);
+    sim.press_wrong();
+    assert_eq!(sim.player_action(), PlayerAction::Fall);
+}
+
+#[test]
+fn culled_clouds_come_back_after_a_lost_life() {
+    let mut sim = playing_with_lives(2);
+    for _ in 0..20 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.run_for(1.);
+
+    for loc in sim.game().climb.tower().platforms().iter().take(10) {
+        assert!(
+            sim.game().platforms.placed().any(|(placed, _)| placed == loc),
+            "no cloud at {loc:?} after going back down"
+        );
+    }
+}
+
+#[test]
+fn replays_keep_their_lives() {
+    let mut sim = playing_with_lives(2);
+    sim.press_correct();
+    sim.press_wrong();
+    sim.press_correct();
+    sim.press_correct();
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.config.lives, 2);
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+
+    sim.resource_mut::<Settings>().climb = ClimbConfig::default();
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.finish_fall();
+    assert_eq!(sim.score(), score);
+}
+
+#[test]
+fn replays_wait_out_a_life_lost_to_the_clock() {
+    let mut sim = playing_with_lives(2);
+    sim.press_correct();
+    sim.press_correct();
+    sim.run_for(CHECK_POINT_TIME + 0.5);
+    sim.settle();
+    assert_eq!(sim.game().climb.lives(), 1);
+    for _ in 0..4 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    assert_eq!(sim.score(), 4);
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.inputs.iter().map(|input| input.lives_lost).collect::<Vec<_>>(), [0, 0, 1, 1, 1, 1, 1]);
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.run_for(CHECK_POINT_TIME + 5.);
+    sim.finish_fall();
+    assert_eq!(sim.game_state(), GameState::GameOver);
+    assert_eq!(sim.score(), 4);
+}
+
+#[test]
+fn lives_show_next_to_the_score() {
+    let mut sim = playing_with_lives(3);
+    sim.press_wrong();
+    sim.step();
+    let world = sim.app.world_mut();
+    let texts: Vec<String> = world
+        .query::<&Text>()
+        .iter(world)
+        .map(|text| text.sections[0].value.clone())
+        .collect();
+    assert!(texts.contains(&String::from("Lives: 2")));
+}
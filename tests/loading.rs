@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use uppy_up::assets::GameAssets;
+use uppy_up::audio::effects::SoundPack;
+use uppy_up::audio::music::Track;
+use uppy_up::headless::Sim;
+use uppy_up::GameState;
+
+fn texts(sim: &mut Sim) -> Vec<String> {
+    let world = sim.app.world_mut();
+    world
+        .query::<&Text>()
+        .iter(world)
+        .map(|text| text.sections[0].value.clone())
+        .collect()
+}
+
+#[test]
+fn every_sound_is_preloaded() {
+    let sim = Sim::new(42);
+    let assets = sim.resource::<GameAssets>();
+    for sound in sim.resource::<SoundPack>().0.values() {
+        for path in &sound.paths {
+            assert!(assets.sounds.contains_key(path), "{path} is not preloaded");
+        }
+    }
+    for track in Track::ALL {
+        assert!(assets.music.contains_key(&track));
+    }
+}
+
+// headless has no image or audio loaders, so everything fails to load
+#[test]
+fn missing_files_hold_the_loading_screen() {
+    let mut sim = Sim::new(42);
+    sim.set_state(GameState::Loading);
+    sim.run_for(1.);
+    assert_eq!(sim.game_state(), GameState::Loading);
+    let message = texts(&mut sim)
+        .into_iter()
+        .find(|text| text.starts_with("Could not load"))
+        .expect("no missing file message");
+    assert!(message.contains("cloud.png"), "{message}");
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use uppy_up::game::input::DirectionInput;
+use uppy_up::game::player::PLAYER_OFFSET;
+use uppy_up::game::{GameMode, Motion, PlayerAction};
+use uppy_up::headless::Sim;
+
+fn playing() -> Sim {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    sim.settle();
+    sim
+}
+
+fn send(sim: &mut Sim) {
+    let direction = sim.next_direction();
+    sim.app.world_mut().send_event(DirectionInput(direction));
+    sim.step();
+}
+
+fn camera_y(sim: &Sim) -> f32 {
+    let camera = sim.game().camera.unwrap();
+    sim.app.world().get::<Transform>(camera).unwrap().translation.y
+}
+
+#[test]
+fn jumps_arc_until_landing() {
+    let mut sim = playing();
+    let start = sim.game().player_location().clone();
+    send(&mut sim);
+    sim.step();
+    // scored as soon as the jump starts
+    assert_eq!(sim.score(), 1);
+    assert_eq!(sim.player_action(), PlayerAction::Jump);
+
+    let landing = sim.game().climb.player().y + PLAYER_OFFSET;
+    let mut highest = start.y;
+    while sim.player_action() == PlayerAction::Jump {
+        highest = highest.max(sim.game().player_location().y);
+        sim.step();
+    }
+    assert!(highest > landing, "the arc should rise above where it lands");
+    assert_eq!(sim.game().player_location().y, landing);
+}
+
+#[test]
+fn presses_mid_jump_are_buffered() {
+    let mut sim = playing();
+    send(&mut sim);
+    sim.step();
+    assert_eq!(sim.player_action(), PlayerAction::Jump);
+    send(&mut sim);
+    assert_eq!(sim.score(), 1);
+    sim.settle();
+    assert_eq!(sim.score(), 2);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+}
+
+#[test]
+fn camera_eases_after_the_player() {
+    let mut sim = playing();
+    let before = camera_y(&sim);
+    sim.press_correct();
+    let after_jump = camera_y(&sim);
+    assert!(after_jump > before);
+
+    sim.run_for(2.);
+    let settled = camera_y(&sim);
+    assert!(settled > after_jump, "the camera should still be catching up after landing");
+    sim.run_for(1.);
+    assert!((camera_y(&sim) - settled).abs() < 0.5);
+}
+
+#[test]
+fn look_ahead_raises_the_camera() {
+    let mut plain = playing();
+    plain.press_correct();
+    plain.run_for(2.);
+
+    let mut ahead = Sim::new(42);
+    ahead.resource_mut::<Motion>().look_ahead = 80.;
+    ahead.start(GameMode::Classic);
+    ahead.press_correct();
+    ahead.run_for(2.);
+
+    assert!((camera_y(&ahead) - camera_y(&plain) - 80.).abs() < 1.);
+}
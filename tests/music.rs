@@ -0,0 +1,46 @@
+use uppy_up::audio::music::{urgency, MusicTrack, Track};
+use uppy_up::game::GameMode;
+use uppy_up::headless::Sim;
+
+fn tracks(sim: &mut Sim) -> Vec<(Track, f32, bool)> {
+    let world = sim.app.world_mut();
+    world
+        .query::<&MusicTrack>()
+        .iter(world)
+        .map(|music| (music.track, music.fade, music.fading_out))
+        .collect()
+}
+
+#[test]
+fn tracks_crossfade_between_states() {
+    let mut sim = Sim::new(42);
+    sim.run_for(2.);
+    assert_eq!(tracks(&mut sim), vec![(Track::Menu, 1., false)]);
+
+    sim.start(GameMode::Classic);
+    sim.run_for(0.5);
+    let playing = tracks(&mut sim);
+    assert_eq!(playing.len(), 2);
+    assert!(playing.iter().any(|(track, fade, out)| *track == Track::Menu && *out && *fade < 1.));
+    assert!(playing.iter().any(|(track, fade, out)| *track == Track::Playing && !*out && *fade > 0.));
+
+    sim.run_for(2.);
+    assert_eq!(tracks(&mut sim), vec![(Track::Playing, 1., false)]);
+
+    sim.press_wrong();
+    sim.finish_fall();
+    sim.run_for(2.);
+    assert_eq!(tracks(&mut sim), vec![(Track::GameOver, 1., false)]);
+}
+
+#[test]
+fn urgency_rises_as_the_timer_runs_out() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    assert_eq!(urgency(sim.game()), 0.);
+
+    let remaining = sim.game().climb.clock().remaining();
+    sim.run_for(remaining - 2.5);
+    let halfway = urgency(sim.game());
+    assert!(halfway > 0.4 && halfway < 0.6, "{halfway}");
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::KeyCode;
+use uppy_up::GameState;
+use uppy_up::game::{GameMode, PlayerAction};
+use uppy_up::game::pause::{PauseButton, PlayState};
+use uppy_up::game::daily::UtcDate;
+use uppy_up::headless::Sim;
+use uppy_up::rules::Difficulty;
+use uppy_up::save::SaveData;
+
+fn playing() -> Sim {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    sim
+}
+
+fn play_state(sim: &Sim) -> PlayState {
+    *sim.resource::<bevy::prelude::State<PlayState>>().get()
+}
+
+#[test]
+fn pausing_freezes_the_timer_and_input() {
+    let mut sim = playing();
+    sim.press_correct();
+    sim.run_for(1.);
+    sim.tap_key(KeyCode::Escape);
+    assert_eq!(play_state(&sim), PlayState::Paused);
+
+    let elapsed = sim.game().climb.clock().elapsed();
+    sim.run_for(30.);
+    assert_eq!(sim.game().climb.clock().elapsed(), elapsed);
+    assert_eq!(sim.game_state(), GameState::Playing);
+
+    let direction = sim.next_direction();
+    sim.press(direction);
+    assert_eq!(sim.score(), 1);
+
+    sim.tap_key(KeyCode::KeyP);
+    assert_eq!(play_state(&sim), PlayState::Running);
+    sim.press_correct();
+    assert_eq!(sim.score(), 2);
+    assert!(sim.game().climb.clock().elapsed() > elapsed);
+}
+
+#[test]
+fn resume_button_unpauses() {
+    let mut sim = playing();
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Resume);
+    assert_eq!(play_state(&sim), PlayState::Running);
+}
+
+#[test]
+fn restart_starts_a_fresh_run() {
+    let mut sim = playing();
+    for _ in 0..5 {
+        sim.press_correct();
+    }
+    sim.run_for(5.);
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Restart);
+    assert_eq!(sim.game_state(), GameState::Playing);
+    assert_eq!(play_state(&sim), PlayState::Running);
+    assert_eq!(sim.score(), 0);
+    assert!(sim.game().climb.clock().elapsed() < 0.1);
+    assert_eq!(sim.game().climb.tower().platforms().len(), 30);
+    sim.press_correct();
+    assert_eq!(sim.score(), 1);
+}
+
+#[test]
+fn quit_goes_back_to_the_menu() {
+    let mut sim = playing();
+    sim.press_correct();
+    sim.press_wrong();
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Quit);
+    sim.step();
+    assert_eq!(sim.game_state(), GameState::Menu);
+    assert_eq!(sim.player_action(), PlayerAction::Rest);
+
+    sim.start(GameMode::Classic);
+    assert_eq!(sim.score(), 0);
+    sim.press_correct();
+    assert_eq!(sim.score(), 1);
+}
+
+#[test]
+fn quitting_still_counts_the_score() {
+    let mut sim = playing();
+    for _ in 0..3 {
+        sim.press_correct();
+    }
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Quit);
+    sim.step();
+    assert_eq!(sim.resource::<SaveData>().high_score(Difficulty::Normal), 3);
+
+    let today = UtcDate::today();
+    sim.start(GameMode::Daily(today));
+    for _ in 0..2 {
+        sim.press_correct();
+    }
+    sim.tap_key(KeyCode::Escape);
+    sim.click(PauseButton::Restart);
+    assert_eq!(sim.resource::<SaveData>().daily_best(&today.to_string()), 2);
+}
+
+#[test]
+fn losing_focus_pauses() {
+    let mut sim = playing();
+    sim.app.world_mut().send_event(bevy::window::WindowFocused {
+        window: bevy::prelude::Entity::PLACEHOLDER,
+        focused: false,
+    });
+    sim.step();
+    sim.step();
+    assert_eq!(play_state(&sim), PlayState::Paused);
+}
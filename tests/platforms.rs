@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use uppy_up::game::GameMode;
+use uppy_up::game::platform::{Platform, PlatformCulling};
+use uppy_up::game::curve::Curve;
+use uppy_up::headless::Sim;
+use uppy_up::rules::{ClimbConfig, DifficultyCurve};
+
+fn platform_entities(sim: &mut Sim) -> usize {
+    let world = sim.app.world_mut();
+    world.query_filtered::<Entity, With<Platform>>().iter(world).count()
+}
+
+fn climb(sim: &mut Sim, jumps: usize) {
+    for _ in 0..jumps {
+        sim.press_correct();
+    }
+    sim.step();
+}
+
+#[test]
+fn platform_count_stays_constant() {
+    let mut sim = Sim::new(42);
+    // the difficulty curve grows the tower as it spaces out hour glasses
+    sim.resource_mut::<Curve>().0 = DifficultyCurve::default();
+    // a doubler or bonus points would throw the score off and detour clouds the count
+    sim.start_custom(ClimbConfig {
+        pickup_chance: 0.,
+        coin_chance: 0.,
+        combo_window: 0.,
+        time_bonus: 0,
+        ..ClimbConfig::default()
+    });
+    climb(&mut sim, 40);
+    let settled = platform_entities(&mut sim);
+    assert_eq!(sim.game().platforms.len(), settled);
+
+    climb(&mut sim, 80);
+    assert_eq!(platform_entities(&mut sim), settled);
+    assert_eq!(sim.score(), 120);
+}
+
+#[test]
+fn every_platform_ahead_has_a_cloud() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    climb(&mut sim, 60);
+
+    let placed: Vec<(Vec2, Entity)> = sim.game().platforms
+        .placed()
+        .map(|(loc, entity)| (Vec2::new(loc.x, loc.y), *entity))
+        .collect();
+    for loc in sim.game().climb.tower().platforms() {
+        let (_, entity) = placed
+            .iter()
+            .find(|(at, _)| *at == Vec2::new(loc.x, loc.y))
+            .expect("platform ahead without a cloud");
+        let transform = sim.app.world().get::<Transform>(*entity).unwrap();
+        assert_eq!(transform.translation.truncate(), Vec2::new(loc.x, loc.y));
+    }
+}
+
+#[test]
+fn cull_distance_is_configurable() {
+    let mut near = Sim::new(42);
+    near.resource_mut::<PlatformCulling>().distance = 0.;
+    near.start(GameMode::Classic);
+    climb(&mut near, 40);
+
+    let mut far = Sim::new(42);
+    far.resource_mut::<PlatformCulling>().distance = 400.;
+    far.start(GameMode::Classic);
+    climb(&mut far, 40);
+
+    assert!(platform_entities(&mut far) > platform_entities(&mut near));
+}
+
+#[test]
+fn clouds_below_the_last_hour_glass_are_forgotten() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    climb(&mut sim, 100);
+    let game = sim.game();
+    assert!(game.climb.check_points_reached() >= 2);
+    // a lost life goes back no further than the last hour glass
+    let respawn = game.climb.respawn().y;
+    assert!(game.platforms.culled().all(|loc| loc.y >= respawn - uppy_up::WINDOW_Y));
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use bevy::prelude::*;
+use uppy_up::game::GameMode;
+use uppy_up::game::platform::Platform;
+use uppy_up::game::power_up::{Pickup, PowerUpHud};
+use uppy_up::game::replay::Playback;
+use uppy_up::headless::Sim;
+use common::climb_until;
+use uppy_up::rules::{
+    Climb, ClimbConfig, Difficulty, Effects, PowerUp, Step, Tower, DOUBLER_TIME, FREEZE_TIME,
+};
+use uppy_up::GameState;
+
+const SEED: u64 = 5;
+
+fn pickups(chance: f32) -> ClimbConfig {
+    ClimbConfig { pickup_chance: chance, ..ClimbConfig::default() }
+}
+
+fn climb_one(climb: &mut Climb) -> Step {
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir)
+}
+
+// climbs until it lands on this power up
+fn collect(climb: &mut Climb, power_up: PowerUp) {
+    climb_until(climb, |climb| climb.tower().pickups()[0] == Some(power_up));
+    assert!(matches!(climb_one(climb), Step::Climbed { pickup: Some(found), .. } if found == power_up));
+}
+
+#[test]
+fn effects_run_out() {
+    let mut effects = Effects::default();
+    effects.add(PowerUp::Doubler);
+    effects.add(PowerUp::Shield);
+    assert_eq!(effects.time_left(PowerUp::Doubler), Some(DOUBLER_TIME));
+    assert_eq!(effects.time_left(PowerUp::Shield), None);
+    effects.tick(DOUBLER_TIME - 1.);
+    assert!(effects.active(PowerUp::Doubler));
+    effects.tick(1.);
+    assert!(!effects.active(PowerUp::Doubler));
+    // the shield waits to be used
+    assert!(effects.active(PowerUp::Shield));
+    assert!(effects.use_shield());
+    assert!(!effects.use_shield());
+}
+
+#[test]
+fn no_pickups_before_the_first_hour_glass() {
+    let mut climb = Climb::with_config(SEED, pickups(1.));
+    let check_point = climb.check_point().clone();
+    let index = climb.tower().platforms().iter().position(|platform| *platform == check_point).unwrap();
+    assert!(climb.tower().pickups()[..=index].iter().all(Option::is_none));
+    while climb.check_points_reached() == 0 {
+        climb_one(&mut climb);
+    }
+    // they show up on the platforms grown from there
+    assert!(climb.tower().pickups().iter().all(Option::is_none));
+    climb_one(&mut climb);
+    assert!(climb.tower().pickups().last().unwrap().is_some());
+}
+
+#[test]
+fn pickups_leave_the_path_alone() {
+    let mut plain = Tower::new(SEED);
+    let mut stocked = Tower::new(SEED);
+    stocked.set_pickups(0.5);
+    for _ in 0..200 {
+        assert_eq!(plain.grow(), stocked.grow());
+    }
+    for power_up in PowerUp::ALL {
+        assert!(stocked.pickups().contains(&Some(power_up)), "no {power_up:?} pickups");
+    }
+}
+
+#[test]
+fn easier_difficulties_have_more_pickups() {
+    let custom = ClimbConfig::default();
+    let chance = |difficulty: Difficulty| difficulty.config(&custom).pickup_chance;
+    assert!(chance(Difficulty::Easy) > chance(Difficulty::Normal));
+    assert!(chance(Difficulty::Normal) > chance(Difficulty::Hard));
+    assert!(chance(Difficulty::Hard) > 0.);
+}
+
+#[test]
+fn a_shield_forgives_one_wrong_step() {
+    let mut climb = Climb::with_config(SEED, pickups(1.));
+    collect(&mut climb, PowerUp::Shield);
+    let player = climb.player().clone();
+    let dir = climb.next_direction().unwrap();
+    assert_eq!(climb.step(dir.opposite()), Step::Shielded);
+    assert_eq!(climb.player(), &player);
+    assert!(!climb.effects().active(PowerUp::Shield));
+    assert_eq!(climb.step(dir.opposite()), Step::WrongDirection);
+}
+
+#[test]
+fn time_freeze_stops_the_hour_glass() {
+    let mut climb = Climb::with_config(SEED, pickups(1.));
+    collect(&mut climb, PowerUp::TimeFreeze);
+    let remaining = climb.clock().remaining();
+    climb.tick(FREEZE_TIME);
+    assert_eq!(climb.clock().remaining(), remaining);
+    climb.tick(1.);
+    assert!(climb.clock().remaining() < remaining);
+}
+
+#[test]
+fn the_doubler_scores_two_a_platform() {
+    let mut climb = Climb::with_config(SEED, pickups(1.));
+    collect(&mut climb, PowerUp::Doubler);
+    let climbing = climb.score_breakdown().climbing;
+    climb_one(&mut climb);
+    assert_eq!(climb.score_breakdown().climbing, climbing + 2);
+    climb.tick(DOUBLER_TIME);
+    climb_one(&mut climb);
+    assert_eq!(climb.score_breakdown().climbing, climbing + 3);
+}
+
+#[test]
+fn pickups_float_over_their_clouds_until_collected() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(pickups(1.));
+    while sim.game().climb.tower().pickups()[0].is_none() {
+        sim.press_correct();
+    }
+    sim.step();
+    let next = sim.game().climb.tower().platforms()[0].clone();
+    let (_, entity) = sim.game().platforms.placed().find(|(placed, _)| *placed == next).cloned().unwrap();
+    let has_pickup = |sim: &mut Sim| {
+        let world = sim.app.world_mut();
+        let children = world.get::<Children>(entity).map_or(0, |children| {
+            children.iter().filter(|child| world.get::<Pickup>(**child).is_some()).count()
+        });
+        (children, world.get::<Platform>(entity).unwrap().pickup)
+    };
+    let (children, pickup) = has_pickup(&mut sim);
+    assert_eq!(children, 1);
+    assert_eq!(pickup, sim.game().climb.tower().pickups()[0]);
+
+    sim.press_correct();
+    sim.step();
+    assert_eq!(has_pickup(&mut sim), (0, None));
+    let collected = pickup.unwrap();
+    assert!(sim.game().climb.effects().active(collected));
+
+    let world = sim.app.world_mut();
+    for (style, PowerUpHud(power_up)) in world.query::<(&Style, &PowerUpHud)>().iter(world) {
+        assert_eq!(style.display == Display::Flex, *power_up == collected);
+    }
+}
+
+#[test]
+fn replays_keep_the_pickups() {
+    let mut sim = Sim::new(SEED);
+    sim.start_custom(pickups(0.5));
+    // far enough past the first hour glass to pick some up
+    for _ in 0..90 {
+        sim.run_for(0.05);
+        sim.press_correct();
+    }
+    assert!(sim.score() > 90);
+    if sim.game().climb.effects().active(PowerUp::Shield) {
+        sim.press_wrong();
+        sim.settle();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    let score = sim.score();
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.config, pickups(0.5));
+    assert_eq!(recorded.to_string().parse(), Ok(recorded.clone()));
+
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.run_for(40.);
+    sim.finish_fall();
+    assert_eq!(sim.score(), score);
+}
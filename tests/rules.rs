@@ -0,0 +1,132 @@
+use uppy_up::rules::{Climb, ClimbConfig, Direction, Location, Step, Tower, CHECK_POINT_TIME, LOOKAHEAD, Y_INC};
+
+#[test]
+fn tower_is_a_chain_of_single_steps() {
+    let climb = Climb::new(7);
+    let tower = climb.tower();
+    assert_eq!(tower.platforms().len(), LOOKAHEAD);
+    let mut loc = Location::default();
+    for (dir, platform) in tower.correct_path().iter().zip(tower.platforms()) {
+        loc = loc.step(*dir);
+        assert_eq!(&loc, platform);
+    }
+    assert_eq!(&loc, tower.top());
+}
+
+#[test]
+fn tower_stays_inside_the_window() {
+    let mut tower = Tower::new(3);
+    for _ in 0..1000 {
+        let platform = tower.grow();
+        assert!(platform.x.abs() <= uppy_up::WINDOW_X / 2. - 50.);
+    }
+}
+
+#[test]
+fn seeds_decide_the_tower() {
+    let a = Climb::new(1);
+    let b = Climb::new(1);
+    let c = Climb::new(2);
+    assert_eq!(a.tower().correct_path(), b.tower().correct_path());
+    assert_ne!(a.tower().correct_path(), c.tower().correct_path());
+}
+
+#[test]
+fn correct_step_climbs_and_scores() {
+    let mut climb = Climb::new(11);
+    let dir = climb.tower().next_direction().unwrap();
+    let target = climb.tower().platforms()[0].clone();
+    let Step::Climbed { check_point, new_platform, .. } = climb.step(dir) else {
+        panic!("correct direction should climb");
+    };
+    assert!(!check_point);
+    assert_eq!(climb.player(), &target);
+    assert_eq!(climb.score(), 1);
+    assert_eq!(climb.tower().platforms().len(), LOOKAHEAD);
+    assert_eq!(climb.tower().top(), &new_platform);
+    assert_eq!(new_platform.y, target.y + Y_INC * LOOKAHEAD as f32);
+}
+
+#[test]
+fn wrong_step_changes_nothing() {
+    let mut climb = Climb::new(11);
+    let dir = climb.tower().next_direction().unwrap();
+    assert_eq!(climb.step(dir.opposite()), Step::WrongDirection);
+    assert_eq!(climb.score(), 0);
+    assert_eq!(climb.player(), &Location::default());
+    assert_eq!(climb.tower().next_direction(), Some(dir));
+}
+
+#[test]
+fn check_point_sits_at_the_lookahead_and_resets_the_clock() {
+    let mut climb = Climb::new(5);
+    let first = climb.check_point().clone();
+    assert_eq!(&first, climb.tower().top());
+
+    climb.tick(CHECK_POINT_TIME - 1.);
+    for _ in 0..LOOKAHEAD - 1 {
+        let dir = climb.tower().next_direction().unwrap();
+        assert!(matches!(climb.step(dir), Step::Climbed { check_point: false, .. }));
+    }
+    let dir = climb.tower().next_direction().unwrap();
+    assert!(matches!(climb.step(dir), Step::Climbed { check_point: true, .. }));
+    assert_eq!(climb.player(), &first);
+    assert_eq!(climb.check_points_reached(), 1);
+    assert_eq!(climb.clock().elapsed(), 0.);
+    // every hour glass is the same number of platforms up from the last one
+    assert_eq!(climb.check_point().y, first.y + Y_INC * LOOKAHEAD as f32);
+}
+
+#[test]
+fn clock_expires_after_check_point_time() {
+    let mut climb = Climb::new(5);
+    assert!(!climb.tick(CHECK_POINT_TIME - 0.01));
+    assert!(climb.tick(0.02));
+    assert_eq!(climb.clock().remaining(), 0.);
+}
+
+#[test]
+fn direction_opposites() {
+    assert_eq!(Direction::Left.opposite(), Direction::Right);
+    assert_eq!(Direction::Right.opposite(), Direction::Left);
+}
+
+#[test]
+fn a_lost_life_goes_back_to_the_last_check_point() {
+    let mut climb = Climb::with_config(9, ClimbConfig { lives: 2, ..ClimbConfig::default() });
+    let path: Vec<Direction> = climb.tower().correct_path().to_vec();
+    for dir in &path[..5] {
+        climb.step(*dir);
+    }
+    assert_eq!(climb.step(path[5].opposite()), Step::WrongDirection);
+
+    assert!(climb.lose_life());
+    assert_eq!(climb.lives(), 1);
+    assert_eq!(climb.player(), &Location::default());
+    assert_eq!(climb.score(), 0);
+    assert_eq!(climb.clock().elapsed(), 0.);
+    // the tower below is kept as it was
+    assert_eq!(&climb.tower().correct_path()[..path.len()], &path[..]);
+
+    assert!(!climb.lose_life());
+    assert_eq!(climb.lives(), 0);
+}
+
+#[test]
+fn reaching_a_check_point_moves_the_respawn() {
+    let mut climb = Climb::with_config(9, ClimbConfig { lives: 3, ..ClimbConfig::default() });
+    let check_point = climb.check_point().clone();
+    while climb.check_points_reached() == 0 {
+        let dir = climb.tower().next_direction().unwrap();
+        climb.step(dir);
+    }
+    let score = climb.score();
+    for _ in 0..3 {
+        let dir = climb.tower().next_direction().unwrap();
+        climb.step(dir);
+    }
+
+    assert!(climb.lose_life());
+    assert_eq!(climb.player(), &check_point);
+    assert_eq!(climb.score(), score);
+}
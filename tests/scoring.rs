@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+use uppy_up::game::GameMode;
+use uppy_up::game::popup::ScorePopup;
+use uppy_up::game::replay::{Playback, Replay};
+use uppy_up::game_over::breakdown_lines;
+use uppy_up::headless::Sim;
+use uppy_up::rules::{
+    Climb, ClimbConfig, Combo, Step, COMBO_STEP, COMBO_WINDOW, MAX_MULTIPLIER, TIME_BONUS,
+};
+use uppy_up::GameState;
+
+const SEED: u64 = 8;
+
+// no pickups, so the doubler stays out of it
+fn config() -> ClimbConfig {
+    ClimbConfig { pickup_chance: 0., lives: 2, ..ClimbConfig::default() }
+}
+
+// climbs a platform, after waiting this long since the last jump
+fn climb_after(climb: &mut Climb, wait: f32) -> Step {
+    climb.tick(wait);
+    let dir = climb.next_direction().unwrap();
+    climb.step(dir)
+}
+
+fn texts(sim: &mut Sim) -> Vec<String> {
+    let world = sim.app.world_mut();
+    world.query::<&Text>()
+        .iter(world)
+        .map(|text| text.sections[0].value.clone())
+        .collect()
+}
+
+#[test]
+fn quick_jumps_build_up_the_multiplier() {
+    let mut combo = Combo::default();
+    for jump in 1..COMBO_STEP {
+        combo.jump(jump as f32 * 0.1, COMBO_WINDOW);
+        assert_eq!(combo.multiplier(), 1);
+    }
+    combo.jump(COMBO_STEP as f32 * 0.1, COMBO_WINDOW);
+    assert_eq!(combo.multiplier(), 2);
+    for jump in 0..COMBO_STEP * 10 {
+        combo.jump(1. + jump as f32 * 0.1, COMBO_WINDOW);
+    }
+    assert_eq!(combo.multiplier(), MAX_MULTIPLIER);
+
+    // too long a wait starts over
+    combo.jump(10., COMBO_WINDOW);
+    assert_eq!(combo.jumps(), 1);
+    assert_eq!(combo.multiplier(), 1);
+}
+
+#[test]
+fn fast_climbers_outscore_slow_ones() {
+    let mut fast = Climb::with_config(SEED, config());
+    let mut slow = Climb::with_config(SEED, config());
+    for _ in 0..12 {
+        climb_after(&mut fast, 0.2);
+        climb_after(&mut slow, COMBO_WINDOW + 0.1);
+    }
+    assert_eq!(slow.score(), 12);
+    assert_eq!(slow.score_breakdown().combo, 0);
+    assert_eq!(fast.score_breakdown().climbing, 12);
+    assert!(fast.score() > slow.score());
+    assert_eq!(fast.score(), fast.score_breakdown().climbing + fast.score_breakdown().combo);
+
+    // the points a step reports are what it added
+    let before = fast.score();
+    let Step::Climbed { points, .. } = climb_after(&mut fast, 0.2) else {
+        panic!("didn't climb");
+    };
+    assert_eq!(points, fast.combo().multiplier());
+    assert_eq!(fast.score(), before + points);
+}
+
+#[test]
+fn time_left_at_an_hour_glass_is_scored() {
+    let mut climb = Climb::with_config(SEED, config());
+    let step = loop {
+        let step = climb_after(&mut climb, 0.5);
+        if climb.check_points_reached() == 1 {
+            break step;
+        }
+    };
+    let Step::Climbed { check_point: true, time_bonus, .. } = step else {
+        panic!("didn't reach the hour glass");
+    };
+    // 30 platforms half a second apart out of 20 seconds
+    assert_eq!(time_bonus, 5 * TIME_BONUS);
+    let breakdown = climb.score_breakdown();
+    assert_eq!(breakdown.time, time_bonus);
+    assert_eq!(breakdown.climbing, 30);
+    assert_eq!(climb.score(), breakdown.climbing + breakdown.combo + time_bonus);
+}
+
+#[test]
+fn a_lost_life_ends_the_combo_and_its_points() {
+    let mut climb = Climb::with_config(SEED, config());
+    for _ in 0..COMBO_STEP + 2 {
+        climb_after(&mut climb, 0.1);
+    }
+    assert!(climb.combo().multiplier() > 1);
+    let dir = climb.next_direction().unwrap();
+    assert_eq!(climb.step(dir.opposite()), Step::WrongDirection);
+    assert!(climb.lose_life());
+    assert_eq!(climb.combo().jumps(), 0);
+    assert_eq!(climb.score(), 0);
+    assert_eq!(*climb.score_breakdown(), Default::default());
+}
+
+#[test]
+fn old_replays_score_a_point_a_platform() {
+    let replay: Replay = "uppy-up replay 6\nseed 3\n".parse().unwrap();
+    assert_eq!(replay.config.combo_window, 0.);
+    assert_eq!(replay.config.time_bonus, 0);
+
+    let mut climb = Climb::with_config(SEED, replay.config);
+    for _ in 0..20 {
+        climb_after(&mut climb, 0.);
+    }
+    assert_eq!(climb.score(), 20);
+}
+
+#[test]
+fn points_pop_up_over_the_player() {
+    let mut sim = Sim::new(SEED);
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    assert!(texts(&mut sim).contains(&String::from("+1")));
+    for _ in 0..COMBO_STEP {
+        sim.press_correct();
+    }
+    assert!(texts(&mut sim).contains(&String::from("+2 x2")));
+
+    // they float off and go
+    sim.run_for(1.);
+    let world = sim.app.world_mut();
+    assert_eq!(world.query::<&ScorePopup>().iter(world).count(), 0);
+}
+
+#[test]
+fn game_over_breaks_the_score_down() {
+    let mut sim = Sim::new(SEED);
+    sim.start(GameMode::Classic);
+    for _ in 0..COMBO_STEP + 2 {
+        sim.press_correct();
+    }
+    sim.press_wrong();
+    sim.finish_fall();
+    let breakdown = *sim.game().climb.score_breakdown();
+    assert_eq!(breakdown.climbing, COMBO_STEP as i32 + 2);
+    assert!(breakdown.combo > 0);
+    assert_eq!(breakdown.time, 0);
+    assert_eq!(sim.score(), breakdown.total());
+
+    let lines = breakdown_lines(&breakdown);
+    assert_eq!(lines, vec![
+        format!("Climbing: {}", breakdown.climbing),
+        format!("Combos: +{}", breakdown.combo),
+    ]);
+    let shown = texts(&mut sim);
+    for line in &lines {
+        assert!(shown.contains(line), "no `{line}` on the game over screen");
+    }
+
+    // and a replay adds up the same
+    let recorded = sim.resource::<Playback>().replay.clone().unwrap();
+    assert_eq!(recorded.to_string().parse(), Ok(recorded));
+    sim.set_state(GameState::Menu);
+    sim.start(GameMode::Replay);
+    sim.finish_fall();
+    assert_eq!(*sim.game().climb.score_breakdown(), breakdown);
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::KeyCode;
+use uppy_up::audio::SoundKind;
+use uppy_up::game::Direction;
+use uppy_up::headless::Sim;
+use uppy_up::save::{load_json, Storage};
+use uppy_up::settings::{AudioSettings, Channel, Settings, SETTINGS_NAME};
+
+#[test]
+fn volume_scales_by_master_and_mute_silences() {
+    let mut audio = AudioSettings {
+        master: 0.5,
+        sfx: 0.8,
+        music: 0.4,
+        muted: false,
+    };
+    assert_eq!(audio.volume(SoundKind::Sfx), 0.4);
+    assert_eq!(audio.volume(SoundKind::Music), 0.2);
+
+    audio.muted = true;
+    assert_eq!(audio.volume(SoundKind::Sfx), 0.);
+    assert_eq!(audio.volume(SoundKind::Music), 0.);
+}
+
+#[test]
+fn volume_steps_stay_in_range() {
+    let mut audio = AudioSettings::default();
+    audio.step(Channel::Master, 1);
+    assert_eq!(audio.channel(Channel::Master), 1.);
+
+    for _ in 0..3 {
+        audio.step(Channel::Music, -1);
+    }
+    assert_eq!(audio.channel(Channel::Music), 0.4);
+
+    for _ in 0..20 {
+        audio.step(Channel::Sfx, -1);
+    }
+    assert_eq!(audio.channel(Channel::Sfx), 0.);
+}
+
+#[test]
+fn mute_hotkey_toggles_and_persists() {
+    let mut sim = Sim::new(42);
+    sim.tap_key(KeyCode::KeyM);
+    assert!(sim.resource::<Settings>().audio.muted);
+
+    let saved: Settings = load_json(sim.resource::<Storage>(), SETTINGS_NAME);
+    assert!(saved.audio.muted);
+
+    sim.tap_key(KeyCode::KeyM);
+    assert!(!sim.resource::<Settings>().audio.muted);
+}
+
+#[test]
+fn mute_hotkey_steps_aside_for_bindings() {
+    let mut sim = Sim::new(42);
+    sim.resource_mut::<Settings>().bindings.bind(Direction::Left, 0, KeyCode::KeyM);
+    sim.step();
+    sim.tap_key(KeyCode::KeyM);
+    assert!(!sim.resource::<Settings>().audio.muted);
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use uppy_up::audio::effects::{Effect, EffectSound, SoundPack};
+use uppy_up::game::{GameEvent, GameMode};
+use uppy_up::headless::Sim;
+
+fn effects(sim: &mut Sim) -> Vec<(GameEvent, Option<String>)> {
+    let world = sim.app.world_mut();
+    let handles: Vec<(GameEvent, Handle<AudioSource>)> = world
+        .query::<(&Effect, &Handle<AudioSource>)>()
+        .iter(world)
+        .map(|(effect, handle)| (effect.event, handle.clone()))
+        .collect();
+    let asset_server = world.resource::<AssetServer>();
+    handles
+        .into_iter()
+        .map(|(event, handle)| (event, asset_server.get_path(&handle).map(|path| path.to_string())))
+        .collect()
+}
+
+fn count(sim: &mut Sim, event: GameEvent) -> usize {
+    effects(sim).iter().filter(|(played, _)| *played == event).count()
+}
+
+#[test]
+fn gameplay_events_make_sounds() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    sim.step();
+    assert_eq!(count(&mut sim, GameEvent::Jumped), 1);
+
+    sim.press_wrong();
+    sim.step();
+    assert_eq!(count(&mut sim, GameEvent::WrongDirection), 1);
+}
+
+#[test]
+fn rapid_jumps_are_capped() {
+    let mut sim = Sim::new(42);
+    sim.start(GameMode::Classic);
+    for _ in 0..10 {
+        sim.press_correct();
+    }
+    sim.step();
+    let cap = sim.resource::<SoundPack>().0[&GameEvent::Jumped].max_playing;
+    assert_eq!(count(&mut sim, GameEvent::Jumped), cap);
+
+    // a burst in one frame is capped too
+    for _ in 0..10 {
+        sim.app.world_mut().send_event(GameEvent::Jumped);
+    }
+    sim.step();
+    assert_eq!(count(&mut sim, GameEvent::Jumped), cap);
+}
+
+#[test]
+fn sound_packs_swap_without_game_changes() {
+    let mut sim = Sim::new(42);
+    sim.resource_mut::<SoundPack>().0.insert(GameEvent::Jumped, EffectSound::new("boing.ogg"));
+    sim.start(GameMode::Classic);
+    sim.press_correct();
+    sim.step();
+    assert_eq!(effects(&mut sim), vec![(GameEvent::Jumped, Some(String::from("boing.ogg")))]);
+}